@@ -0,0 +1,174 @@
+#import bevy_sprite::mesh2d_view_bindings
+#import bevy_pbr::utils
+
+@group(1) @binding(0)
+var texture: texture_2d<f32>;
+
+@group(1) @binding(1)
+var our_sampler: sampler;
+
+// x is the effect id, y its strength. The first layer without strength ends the stack.
+@group(1) @binding(2)
+var<uniform> stack: array<vec4<f32>, 8>;
+
+fn trippy(color: vec3<f32>, uv: vec2<f32>, strength: f32) -> vec3<f32> {
+    let offset = sin(globals.time) * 0.002 * strength;
+    let r = textureSample(texture, our_sampler, uv + vec2<f32>(offset, -offset)).r;
+    let g = textureSample(texture, our_sampler, uv + vec2<f32>(offset, 0.0)).g;
+    let b = textureSample(texture, our_sampler, uv + vec2<f32>(0.0, offset)).b;
+    return mix(color, vec3<f32>(g, b, r), min(strength, 1.0));
+}
+
+fn nausea(color: vec3<f32>, uv: vec2<f32>, strength: f32) -> vec3<f32> {
+    let sway = vec2<f32>(sin(globals.time * 1.3 + uv.y * 6.0) * 0.004 * strength, 0.0);
+    let radius = 0.003 * strength;
+
+    var blurred = vec3<f32>(0.0);
+    for (var x = -1; x <= 1; x = x + 1) {
+        for (var y = -1; y <= 1; y = y + 1) {
+            let offset = vec2<f32>(f32(x), f32(y)) * radius;
+            blurred += textureSample(texture, our_sampler, uv + sway + offset).rgb;
+        }
+    }
+    return mix(color, blurred / 9.0, min(strength, 1.0));
+}
+
+fn blackout(color: vec3<f32>, uv: vec2<f32>, strength: f32) -> vec3<f32> {
+    let vignette = smoothstep(0.75, 0.2, distance(uv, vec2<f32>(0.5)));
+    return color * mix(1.0, vignette, min(strength, 1.0));
+}
+
+fn radiation(color: vec3<f32>, position: vec2<f32>, strength: f32) -> vec3<f32> {
+    let noise = fract(sin(dot(position + globals.time, vec2<f32>(12.9898, 78.233))) * 43758.5453);
+    let tinted = mix(color, color * vec3<f32>(0.8, 1.1, 0.75), 0.5 * strength);
+    return tinted + (noise - 0.5) * 0.2 * strength;
+}
+
+fn high_contrast(color: vec3<f32>, strength: f32) -> vec3<f32> {
+    let luma = dot(color, vec3<f32>(0.299, 0.587, 0.114));
+    let saturated = mix(vec3<f32>(luma), color, 1.0 + 0.5 * strength);
+    return clamp((saturated - 0.5) * (1.0 + 0.6 * strength) + 0.5, vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+// Daltonisation: simulates how the deficiency sees the colour in LMS space, then moves what got
+// lost into the channels that are still told apart. 5 is protanopia, 6 deuteranopia and 7
+// tritanopia.
+fn daltonize(color: vec3<f32>, deficiency: u32, strength: f32) -> vec3<f32> {
+    let l = dot(color, vec3<f32>(17.8824, 43.5161, 4.11935));
+    let m = dot(color, vec3<f32>(3.45565, 27.1554, 3.86714));
+    let s = dot(color, vec3<f32>(0.0299566, 0.184309, 1.46709));
+
+    var lms = vec3<f32>(l, m, s);
+    switch deficiency {
+        case 5u: {
+            lms.x = 2.02344 * m - 2.52581 * s;
+        }
+        case 6u: {
+            lms.y = 0.494207 * l + 1.24827 * s;
+        }
+        default: {
+            lms.z = -0.395913 * l + 0.801109 * m;
+        }
+    }
+
+    let seen = vec3<f32>(
+        dot(lms, vec3<f32>(0.0809444479, -0.130504409, 0.116721066)),
+        dot(lms, vec3<f32>(-0.0102485335, 0.0540193266, -0.113614708)),
+        dot(lms, vec3<f32>(-0.000365296938, -0.00412161469, 0.693511405)),
+    );
+    let lost = color - seen;
+    let shift = vec3<f32>(0.0, 0.7 * lost.r + lost.g, 0.7 * lost.r + lost.b);
+
+    let corrected = clamp(color + shift, vec3<f32>(0.0), vec3<f32>(1.0));
+    return mix(color, corrected, min(strength, 1.0));
+}
+
+// Ordered dithering in the pixels of the offscreen image, then the closest colour of the
+// PICO-8 palette
+fn retro(color: vec3<f32>, uv: vec2<f32>, strength: f32) -> vec3<f32> {
+    var bayer = array<f32, 16>(
+        0.0, 8.0, 2.0, 10.0,
+        12.0, 4.0, 14.0, 6.0,
+        3.0, 11.0, 1.0, 9.0,
+        15.0, 7.0, 13.0, 5.0,
+    );
+    var palette = array<vec3<f32>, 16>(
+        vec3<f32>(0.0, 0.0, 0.0),
+        vec3<f32>(0.114, 0.169, 0.325),
+        vec3<f32>(0.494, 0.145, 0.325),
+        vec3<f32>(0.0, 0.529, 0.318),
+        vec3<f32>(0.671, 0.322, 0.212),
+        vec3<f32>(0.373, 0.341, 0.310),
+        vec3<f32>(0.761, 0.765, 0.780),
+        vec3<f32>(1.0, 0.945, 0.910),
+        vec3<f32>(1.0, 0.0, 0.302),
+        vec3<f32>(1.0, 0.639, 0.0),
+        vec3<f32>(1.0, 0.925, 0.153),
+        vec3<f32>(0.0, 0.894, 0.212),
+        vec3<f32>(0.161, 0.678, 1.0),
+        vec3<f32>(0.514, 0.463, 0.612),
+        vec3<f32>(1.0, 0.467, 0.659),
+        vec3<f32>(1.0, 0.800, 0.667),
+    );
+
+    let pixel = vec2<u32>(uv * vec2<f32>(textureDimensions(texture)));
+    let threshold = bayer[(pixel.y % 4u) * 4u + pixel.x % 4u] / 16.0 - 0.5;
+    let dithered = color + threshold * 0.15;
+
+    var closest = palette[0];
+    var best = distance(dithered, closest);
+    for (var i = 1u; i < 16u; i = i + 1u) {
+        let candidate = distance(dithered, palette[i]);
+        if candidate < best {
+            best = candidate;
+            closest = palette[i];
+        }
+    }
+    return mix(color, closest, min(strength, 1.0));
+}
+
+@fragment
+fn fragment(
+    @builtin(position) position: vec4<f32>,
+    #import bevy_sprite::mesh2d_vertex_output
+) -> @location(0) vec4<f32> {
+    let uv = coords_to_viewport_uv(position.xy, view.viewport);
+    var color = textureSample(texture, our_sampler, uv).rgb;
+
+    // Each layer works on the result of the ones below it
+    for (var i = 0u; i < 8u; i = i + 1u) {
+        let layer = stack[i];
+        let strength = layer.y;
+        if strength <= 0.0 {
+            break;
+        }
+
+        // Effect ids, as in `PostEffect::shader_id`
+        switch u32(layer.x) {
+            case 0u: {
+                color = trippy(color, uv, strength);
+            }
+            case 1u: {
+                color = nausea(color, uv, strength);
+            }
+            case 2u: {
+                color = blackout(color, uv, strength);
+            }
+            case 3u: {
+                color = radiation(color, position.xy, strength);
+            }
+            case 4u: {
+                color = high_contrast(color, strength);
+            }
+            case 5u, 6u, 7u: {
+                color = daltonize(color, u32(layer.x), strength);
+            }
+            case 8u: {
+                color = retro(color, uv, strength);
+            }
+            default: {}
+        }
+    }
+
+    return vec4<f32>(color, 1.0);
+}
@@ -7,6 +7,16 @@ mod plugins;
 mod states;
 
 fn main() {
+    if let Some(turns) = states::balance::turns_from_args() {
+        if let Err(err) = states::balance::run(turns) {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let settings = plugins::settings::Settings::load();
+
     App::new()
         .insert_resource(Msaa::Off)
         .insert_resource(ClearColor(Color::rgb(0.2, 0.2, 0.2)))
@@ -15,7 +25,8 @@ fn main() {
                 .set(WindowPlugin {
                     primary_window: Some(Window {
                         title: "Junk-Mart".to_string(),
-                        resolution: (800., 600.).into(),
+                        resolution: settings.resolution.into(),
+                        mode: settings.window_mode(),
                         canvas: Some("#bevy".to_owned()),
                         resize_constraints: WindowResizeConstraints {
                             min_width: 640.0,
@@ -31,12 +42,16 @@ fn main() {
                     level: bevy::log::Level::DEBUG,
                 }),
         )
+        .insert_resource(settings)
+        .add_plugin(plugins::settings::SettingsPlugin)
+        .add_plugin(plugins::locale::LocalePlugin)
+        .add_plugin(plugins::audio::AudioChannelsPlugin)
         .add_plugin(plugins::post_process::PostProcessingPlugin)
+        .add_plugin(plugins::capture::CapturePlugin)
         .add_plugin(states::StatePlugin)
         .add_plugin(bevy_egui::EguiPlugin)
         .add_plugin(bevy_tweening::TweeningPlugin)
         .add_plugin(bevy_kira_audio::AudioPlugin)
-        // .add_plugin(bevy_inspector_egui::quick::WorldInspectorPlugin::new())
         .add_system(set_window_icon.on_startup())
         .run();
 }
@@ -0,0 +1,55 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_kira_audio::prelude::*;
+
+use super::settings::Settings;
+
+#[derive(Resource)]
+pub struct Sfx;
+#[derive(Resource)]
+pub struct Music;
+
+#[derive(SystemParam)]
+pub struct Channels<'w> {
+    pub sfx: Res<'w, AudioChannel<Sfx>>,
+    pub music: Res<'w, AudioChannel<Music>>,
+}
+
+impl<'w> Channels<'w> {
+    pub fn pause(&self) {
+        self.sfx.pause();
+        self.music.pause();
+    }
+
+    pub fn resume(&self) {
+        self.sfx.resume();
+        self.music.resume();
+    }
+
+    pub fn stop(&self) {
+        self.sfx.stop();
+        self.music.stop();
+    }
+}
+
+fn apply_volumes(settings: Res<Settings>, audio: Channels) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    audio
+        .sfx
+        .set_volume(settings.master_volume * settings.sfx_volume);
+    audio
+        .music
+        .set_volume(settings.master_volume * settings.music_volume);
+}
+
+pub struct AudioChannelsPlugin;
+
+impl Plugin for AudioChannelsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_audio_channel::<Sfx>()
+            .add_audio_channel::<Music>()
+            .add_system(apply_volumes);
+    }
+}
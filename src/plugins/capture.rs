@@ -0,0 +1,542 @@
+use std::{
+    collections::VecDeque,
+    fs::{self, File},
+    io::BufWriter,
+    num::NonZeroU32,
+    path::PathBuf,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::{
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_asset::RenderAssets,
+        render_resource::{
+            Buffer, BufferDescriptor, BufferUsages, CommandEncoderDescriptor, Extent3d,
+            ImageCopyBuffer, ImageDataLayout, MapMode, TextureDescriptor, TextureDimension,
+            TextureFormat, TextureUsages,
+        },
+        renderer::{render_system, RenderDevice, RenderQueue},
+        texture::BevyDefault,
+        Extract, ExtractSchedule, RenderApp, RenderSet,
+    },
+    tasks::{AsyncComputeTaskPool, IoTaskPool},
+};
+use bevy_egui::{egui, EguiContexts};
+use image::{imageops, ImageFormat, RgbaImage};
+
+use super::{
+    input::{Action, Actions},
+    locale::{Arg, Message, Texts},
+    post_process,
+    settings::Settings,
+};
+
+// Seconds of play the clip buffer keeps
+const CLIP_SECONDS: f64 = 5.0;
+const CLIP_FPS: u16 = 10;
+// Clip frames are scaled down to this width, to keep the buffer and the files small
+const CLIP_WIDTH: u32 = 480;
+// Seconds the outcome of a capture stays on screen
+const OUTCOME_TIME: f64 = 4.0;
+
+// Renders the post-processed quad a second time, into an image that can be read back, on the
+// frames that are captured
+#[derive(Component)]
+struct CaptureCamera;
+
+#[derive(Clone, Copy, Default)]
+struct Wanted {
+    screenshot: bool,
+    clip: bool,
+}
+
+impl Wanted {
+    fn any(&self) -> bool {
+        self.screenshot || self.clip
+    }
+}
+
+// What the render world should copy back this frame
+#[derive(Clone, Default, Resource)]
+struct CaptureRequest {
+    image: Handle<Image>,
+    wanted: Wanted,
+}
+
+// A captured frame on its way from the render world, in 8 bit sRGB RGBA
+struct Frame {
+    image: RgbaImage,
+    wanted: Wanted,
+}
+
+#[derive(Resource)]
+struct FrameSender(Sender<Frame>);
+
+#[derive(Resource)]
+struct Frames(Mutex<Receiver<Frame>>);
+
+// What the saving tasks report back
+#[derive(Resource)]
+struct Outcomes {
+    sender: Sender<Message>,
+    receiver: Mutex<Receiver<Message>>,
+}
+
+// Clip frames scaled down by background tasks, with the time each was taken
+#[derive(Resource)]
+struct ScaledFrames {
+    sender: Sender<(f64, RgbaImage)>,
+    receiver: Mutex<Receiver<(f64, RgbaImage)>>,
+}
+
+// The last few seconds, oldest first, with the time each frame was taken
+#[derive(Default, Resource)]
+struct ClipBuffer {
+    frames: VecDeque<(f64, RgbaImage)>,
+    next_frame: f64,
+}
+
+// A copy of the capture image into a buffer, waiting for the GPU to finish it
+struct Readback {
+    buffer: Buffer,
+    size: UVec2,
+    padded_row: usize,
+    format: TextureFormat,
+    wanted: Wanted,
+    // Set by the mapping callback, with whether it succeeded
+    mapped: Arc<Mutex<Option<bool>>>,
+}
+
+impl Readback {
+    fn frame(&self) -> Option<Frame> {
+        let row = self.size.x as usize * 4;
+        let mut pixels = Vec::with_capacity(row * self.size.y as usize);
+        {
+            let data = self.buffer.slice(..).get_mapped_range();
+            for padded in data.chunks(self.padded_row) {
+                pixels.extend_from_slice(&padded[..row]);
+            }
+        }
+        self.buffer.unmap();
+
+        if matches!(
+            self.format,
+            TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb
+        ) {
+            for pixel in pixels.chunks_exact_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+
+        let image = RgbaImage::from_raw(self.size.x, self.size.y, pixels)?;
+        Some(Frame {
+            image,
+            wanted: self.wanted,
+        })
+    }
+}
+
+fn capture_path(kind: &str, extension: &str) -> Result<PathBuf, String> {
+    let dir = dirs::data_dir()
+        .ok_or("no data directory")?
+        .join("junkmart")
+        .join("captures");
+    fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
+
+    // Milliseconds, so that screenshots taken in quick succession do not overwrite each other
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_millis())
+        .unwrap_or_default();
+    Ok(dir.join(format!("{kind}-{time}.{extension}")))
+}
+
+fn save_screenshot(image: &RgbaImage) -> Result<PathBuf, String> {
+    let path = capture_path("screenshot", "png")?;
+    image
+        .save_with_format(&path, ImageFormat::Png)
+        .map_err(|err| err.to_string())?;
+    Ok(path)
+}
+
+// Writes the frames as an endlessly looping animated PNG
+fn save_clip(frames: &[RgbaImage]) -> Result<PathBuf, String> {
+    let Some(first) = frames.first() else { return Err("no frames".to_string()) };
+    let path = capture_path("clip", "apng")?;
+
+    let file = File::create(&path).map_err(|err| err.to_string())?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), first.width(), first.height());
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .set_animated(frames.len() as u32, 0)
+        .and_then(|_| encoder.set_frame_delay(1, CLIP_FPS))
+        .map_err(|err| err.to_string())?;
+
+    let mut writer = encoder.write_header().map_err(|err| err.to_string())?;
+    for frame in frames {
+        writer
+            .write_image_data(frame.as_raw())
+            .map_err(|err| err.to_string())?;
+    }
+    writer.finish().map_err(|err| err.to_string())?;
+
+    Ok(path)
+}
+
+// Encodes and writes away from the main thread, the outcome comes back as a message
+fn spawn_save(
+    outcomes: &Outcomes,
+    saved_key: &'static str,
+    save: impl FnOnce() -> Result<PathBuf, String> + Send + 'static,
+) {
+    let sender = outcomes.sender.clone();
+    IoTaskPool::get()
+        .spawn(async move {
+            let message = match save() {
+                Ok(path) => {
+                    bevy::log::debug!("Saved a capture to {}", path.display());
+                    Message::new(saved_key).with("path", Arg::Text(path.display().to_string()))
+                }
+                Err(err) => {
+                    bevy::log::error!("Could not save a capture: {err}");
+                    Message::new("capture.failed").with("error", Arg::Text(err))
+                }
+            };
+            // The receiver only goes away with the app
+            let _ = sender.send(message);
+        })
+        .detach();
+}
+
+fn setup_capture(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    windows: Query<&Window>,
+    mut request: ResMut<CaptureRequest>,
+) {
+    let window = windows.single();
+    let size = post_process::physical_size(window);
+
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: None,
+            size: Extent3d {
+                width: size.x,
+                height: size.y,
+                ..default()
+            },
+            dimension: TextureDimension::D2,
+            format: TextureFormat::bevy_default(),
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_SRC
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    image.resize(image.texture_descriptor.size);
+
+    request.image = images.add(image);
+
+    commands.spawn((
+        Camera2dBundle {
+            camera: Camera {
+                order: 2,
+                is_active: false,
+                target: RenderTarget::Image(request.image.clone()),
+                ..default()
+            },
+            ..Camera2dBundle::default()
+        },
+        post_process::render_layer(),
+        CaptureCamera,
+    ));
+}
+
+// Keeps the capture image at the physical size of the window. The quad is sized in logical
+// pixels, so the camera zooms out by the scale factor to fit all of it.
+fn fit_capture_target(
+    windows: Query<&Window>,
+    request: Res<CaptureRequest>,
+    mut images: ResMut<Assets<Image>>,
+    mut cameras: Query<&mut OrthographicProjection, With<CaptureCamera>>,
+    mut fitted: Local<Option<(UVec2, f64)>>,
+) {
+    let Ok(window) = windows.get_single() else { return };
+
+    let size = post_process::physical_size(window);
+    if size.x == 0 || size.y == 0 || *fitted == Some((size, window.scale_factor())) {
+        return;
+    }
+    *fitted = Some((size, window.scale_factor()));
+
+    if let Some(image) = images.get_mut(&request.image) {
+        image.resize(Extent3d {
+            width: size.x,
+            height: size.y,
+            ..default()
+        });
+    }
+    for mut projection in cameras.iter_mut() {
+        projection.scale = 1.0 / window.scale_factor() as f32;
+    }
+}
+
+// Asks for a frame when a screenshot is taken or the clip buffer is due one, and only renders
+// the capture image on those frames
+fn request_frames(
+    mut actions: Actions,
+    settings: Res<Settings>,
+    time: Res<Time>,
+    mut clip_buffer: ResMut<ClipBuffer>,
+    mut request: ResMut<CaptureRequest>,
+    mut cameras: Query<&mut Camera, With<CaptureCamera>>,
+) {
+    let now = time.elapsed_seconds_f64();
+    let clip = settings.clip_buffer && now >= clip_buffer.next_frame;
+    if clip {
+        clip_buffer.next_frame = now + 1.0 / CLIP_FPS as f64;
+    }
+
+    request.wanted = Wanted {
+        screenshot: actions.just_pressed(Action::Screenshot),
+        clip,
+    };
+    for mut camera in cameras.iter_mut() {
+        camera.is_active = request.wanted.any();
+    }
+}
+
+fn scale_for_clip(image: &RgbaImage) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    let scaled_height = (height * CLIP_WIDTH / width.max(1)).max(1);
+    imageops::thumbnail(image, CLIP_WIDTH, scaled_height)
+}
+
+fn receive_frames(
+    frames: Res<Frames>,
+    settings: Res<Settings>,
+    time: Res<Time>,
+    mut clip_buffer: ResMut<ClipBuffer>,
+    scaled_frames: Res<ScaledFrames>,
+    outcomes: Res<Outcomes>,
+) {
+    let now = time.elapsed_seconds_f64();
+
+    for frame in frames.0.lock().unwrap().try_iter() {
+        if frame.wanted.screenshot {
+            let image = frame.image.clone();
+            spawn_save(&outcomes, "capture.screenshot_saved", move || {
+                save_screenshot(&image)
+            });
+        }
+
+        // Scaling a full frame down takes too long for the main thread
+        if frame.wanted.clip && settings.clip_buffer {
+            let sender = scaled_frames.sender.clone();
+            let image = frame.image;
+            AsyncComputeTaskPool::get()
+                .spawn(async move {
+                    // The receiver only goes away with the app
+                    let _ = sender.send((now, scale_for_clip(&image)));
+                })
+                .detach();
+        }
+    }
+
+    for (taken, scaled) in scaled_frames.receiver.lock().unwrap().try_iter() {
+        // An animation cannot change its size halfway through
+        let resized = matches!(clip_buffer.frames.back(), Some((_, last))
+            if last.dimensions() != scaled.dimensions());
+        if resized {
+            clip_buffer.frames.clear();
+        }
+
+        // The tasks do not necessarily finish in the order they were started
+        let index = clip_buffer
+            .frames
+            .partition_point(|(time, _)| *time <= taken);
+        clip_buffer.frames.insert(index, (taken, scaled));
+    }
+
+    if !settings.clip_buffer {
+        clip_buffer.frames.clear();
+        return;
+    }
+    while matches!(clip_buffer.frames.front(), Some((taken, _)) if now - taken > CLIP_SECONDS) {
+        clip_buffer.frames.pop_front();
+    }
+}
+
+fn export_clip(mut actions: Actions, clip_buffer: Res<ClipBuffer>, outcomes: Res<Outcomes>) {
+    if !actions.just_pressed(Action::SaveClip) {
+        return;
+    }
+
+    if clip_buffer.frames.is_empty() {
+        // The sender lives as long as the receiver next to it
+        let _ = outcomes.sender.send(Message::new("capture.clip_empty"));
+        return;
+    }
+
+    let frames: Vec<RgbaImage> = clip_buffer
+        .frames
+        .iter()
+        .map(|(_, frame)| frame.clone())
+        .collect();
+    spawn_save(&outcomes, "capture.clip_saved", move || save_clip(&frames));
+}
+
+// Shows where the last capture went, or why it failed, for a few seconds
+fn show_outcome(
+    mut contexts: EguiContexts,
+    texts: Texts,
+    time: Res<Time>,
+    outcomes: Res<Outcomes>,
+    mut shown: Local<Option<(Message, f64)>>,
+) {
+    let now = time.elapsed_seconds_f64();
+    if let Some(message) = outcomes.receiver.lock().unwrap().try_iter().last() {
+        *shown = Some((message, now + OUTCOME_TIME));
+    }
+
+    let Some((message, until)) = shown.as_ref() else { return };
+    if now > *until {
+        *shown = None;
+        return;
+    }
+
+    egui::Area::new("capture_outcome")
+        .anchor(egui::Align2::LEFT_BOTTOM, [10.0, -10.0])
+        .show(contexts.ctx_mut(), |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.label(texts.message(message));
+            });
+        });
+}
+
+fn extract_request(mut commands: Commands, request: Extract<Res<CaptureRequest>>) {
+    commands.insert_resource(request.clone());
+}
+
+// Hands back the copies of earlier frames that are done, then copies this frame's capture image
+// if one was asked for. Runs after the frame was rendered.
+fn read_back(
+    request: Res<CaptureRequest>,
+    images: Res<RenderAssets<Image>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    sender: Res<FrameSender>,
+    mut in_flight: Local<Vec<Readback>>,
+) {
+    let mut waiting = Vec::new();
+    for readback in in_flight.drain(..) {
+        let mapped = *readback.mapped.lock().unwrap();
+        match mapped {
+            None => waiting.push(readback),
+            Some(false) => bevy::log::error!("Could not read a captured frame back"),
+            Some(true) => {
+                if let Some(frame) = readback.frame() {
+                    // The receiver only goes away with the app
+                    let _ = sender.0.send(frame);
+                }
+            }
+        }
+    }
+    *in_flight = waiting;
+
+    if !request.wanted.any() {
+        return;
+    }
+    let Some(gpu_image) = images.get(&request.image) else { return };
+
+    let size = gpu_image.size.as_uvec2();
+    let padded_row = RenderDevice::align_copy_bytes_per_row(size.x as usize * 4);
+    let buffer = render_device.create_buffer(&BufferDescriptor {
+        label: Some("capture_buffer"),
+        size: (padded_row * size.y as usize) as u64,
+        usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = render_device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("capture_encoder"),
+    });
+    encoder.copy_texture_to_buffer(
+        gpu_image.texture.as_image_copy(),
+        ImageCopyBuffer {
+            buffer: &buffer,
+            layout: ImageDataLayout {
+                offset: 0,
+                bytes_per_row: NonZeroU32::new(padded_row as u32),
+                rows_per_image: None,
+            },
+        },
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            ..default()
+        },
+    );
+    render_queue.submit([encoder.finish()]);
+
+    // The callback runs while a later frame is submitted
+    let mapped = Arc::new(Mutex::new(None));
+    let result = mapped.clone();
+    buffer.slice(..).map_async(MapMode::Read, move |outcome| {
+        *result.lock().unwrap() = Some(outcome.is_ok());
+    });
+
+    in_flight.push(Readback {
+        buffer,
+        size,
+        padded_row,
+        format: gpu_image.texture_format,
+        wanted: request.wanted,
+        mapped,
+    });
+}
+
+pub struct CapturePlugin;
+
+impl Plugin for CapturePlugin {
+    fn build(&self, app: &mut App) {
+        let (frame_sender, frame_receiver) = mpsc::channel();
+        let (outcome_sender, outcome_receiver) = mpsc::channel();
+        let (scaled_sender, scaled_receiver) = mpsc::channel();
+
+        app.init_resource::<CaptureRequest>()
+            .init_resource::<ClipBuffer>()
+            .insert_resource(Frames(Mutex::new(frame_receiver)))
+            .insert_resource(ScaledFrames {
+                sender: scaled_sender,
+                receiver: Mutex::new(scaled_receiver),
+            })
+            .insert_resource(Outcomes {
+                sender: outcome_sender,
+                receiver: Mutex::new(outcome_receiver),
+            })
+            .add_startup_system(setup_capture)
+            .add_system(fit_capture_target)
+            .add_system(request_frames)
+            .add_system(receive_frames)
+            .add_system(export_clip)
+            .add_system(show_outcome);
+
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else { return };
+        render_app
+            .init_resource::<CaptureRequest>()
+            .insert_resource(FrameSender(frame_sender))
+            .add_system(extract_request.in_schedule(ExtractSchedule))
+            .add_system(read_back.in_set(RenderSet::Render).after(render_system));
+    }
+}
@@ -0,0 +1,235 @@
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap};
+use bevy_egui::{egui, EguiContexts};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    locale::{Arg, Message, Texts},
+    settings::Settings,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Pause,
+    SkipAnimation,
+    ToggleNotes,
+    Screenshot,
+    SaveClip,
+}
+
+impl Action {
+    pub const ALL: [Action; 10] = [
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::Confirm,
+        Action::Pause,
+        Action::SkipAnimation,
+        Action::ToggleNotes,
+        Action::Screenshot,
+        Action::SaveClip,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Action::Up => "action.up",
+            Action::Down => "action.down",
+            Action::Left => "action.left",
+            Action::Right => "action.right",
+            Action::Confirm => "action.confirm",
+            Action::Pause => "action.pause",
+            Action::SkipAnimation => "action.skip_animation",
+            Action::ToggleNotes => "action.toggle_notes",
+            Action::Screenshot => "action.screenshot",
+            Action::SaveClip => "action.save_clip",
+        }
+    }
+
+    fn default_bindings(&self) -> Vec<Binding> {
+        use Binding::*;
+
+        match self {
+            Action::Up => vec![
+                Key(KeyCode::Up),
+                Key(KeyCode::W),
+                Gamepad(GamepadButtonType::DPadUp),
+            ],
+            Action::Down => vec![
+                Key(KeyCode::Down),
+                Key(KeyCode::S),
+                Gamepad(GamepadButtonType::DPadDown),
+            ],
+            Action::Left => vec![
+                Key(KeyCode::Left),
+                Key(KeyCode::A),
+                Gamepad(GamepadButtonType::DPadLeft),
+            ],
+            Action::Right => vec![
+                Key(KeyCode::Right),
+                Key(KeyCode::D),
+                Gamepad(GamepadButtonType::DPadRight),
+            ],
+            Action::Confirm => vec![
+                Mouse(MouseButton::Left),
+                Key(KeyCode::Return),
+                Key(KeyCode::Space),
+                Gamepad(GamepadButtonType::South),
+            ],
+            Action::Pause => vec![Key(KeyCode::Escape), Gamepad(GamepadButtonType::Start)],
+            Action::SkipAnimation => vec![Key(KeyCode::Tab), Gamepad(GamepadButtonType::East)],
+            Action::ToggleNotes => vec![Key(KeyCode::N), Gamepad(GamepadButtonType::North)],
+            Action::Screenshot => vec![Key(KeyCode::F12)],
+            Action::SaveClip => vec![Key(KeyCode::F10)],
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButtonType),
+}
+
+impl Binding {
+    // Key and button names stay as they are printed on the keyboard or pad
+    pub fn describe(&self, texts: &Texts) -> String {
+        match self {
+            Binding::Key(key) => format!("{key:?}"),
+            Binding::Mouse(button) => texts.message(
+                &Message::new("binding.mouse").with("button", Arg::Text(format!("{button:?}"))),
+            ),
+            Binding::Gamepad(button) => texts.message(
+                &Message::new("binding.gamepad").with("button", Arg::Text(format!("{button:?}"))),
+            ),
+        }
+    }
+
+    fn same_device(&self, other: &Binding) -> bool {
+        matches!(
+            (self, other),
+            (Binding::Key(_), Binding::Key(_))
+                | (Binding::Mouse(_), Binding::Mouse(_))
+                | (Binding::Gamepad(_), Binding::Gamepad(_))
+        )
+    }
+}
+
+// Actions missing from the settings file keep their default bindings
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bindings(HashMap<Action, Vec<Binding>>);
+
+impl Bindings {
+    pub fn get(&self, action: Action) -> Vec<Binding> {
+        self.0
+            .get(&action)
+            .cloned()
+            .unwrap_or_else(|| action.default_bindings())
+    }
+
+    // A new binding only replaces the ones on the same device, so that rebinding a key keeps
+    // the mouse button and the gamepad button of the action
+    pub fn rebind(&mut self, action: Action, binding: Binding) {
+        let mut bindings = self.get(action);
+        bindings.retain(|old| !old.same_device(&binding));
+        bindings.push(binding);
+        self.0.insert(action, bindings);
+    }
+}
+
+#[derive(SystemParam)]
+pub struct Actions<'w, 's> {
+    settings: Res<'w, Settings>,
+    keys: Res<'w, Input<KeyCode>>,
+    mouse: Res<'w, Input<MouseButton>>,
+    buttons: Res<'w, Input<GamepadButton>>,
+    contexts: EguiContexts<'w, 's>,
+}
+
+impl<'w, 's> Actions<'w, 's> {
+    // Keys typed into a text field and clicks on a window belong to the UI
+    pub fn just_pressed(&mut self, action: Action) -> bool {
+        let ctx = self.contexts.ctx_mut();
+        let keyboard_taken = ctx.wants_keyboard_input();
+        let pointer_taken = ctx.is_pointer_over_area();
+
+        self.settings
+            .bindings
+            .get(action)
+            .iter()
+            .any(|binding| match *binding {
+                Binding::Key(key) => !keyboard_taken && self.keys.just_pressed(key),
+                Binding::Mouse(button) => !pointer_taken && self.mouse.just_pressed(button),
+                Binding::Gamepad(button) => self
+                    .buttons
+                    .get_just_pressed()
+                    .any(|pressed| pressed.button_type == button),
+            })
+    }
+}
+
+// The first key, mouse or gamepad button pressed this frame, for rebinding
+pub fn pressed_binding(
+    keys: &Input<KeyCode>,
+    mouse: &Input<MouseButton>,
+    buttons: &Input<GamepadButton>,
+) -> Option<Binding> {
+    keys.get_just_pressed()
+        .next()
+        .map(|key| Binding::Key(*key))
+        .or_else(|| {
+            mouse
+                .get_just_pressed()
+                .next()
+                .map(|button| Binding::Mouse(*button))
+        })
+        .or_else(|| {
+            buttons
+                .get_just_pressed()
+                .next()
+                .map(|button| Binding::Gamepad(button.button_type))
+        })
+}
+
+pub fn bindings_ui(
+    ui: &mut egui::Ui,
+    texts: &Texts,
+    bindings: &mut Bindings,
+    rebinding: &mut Option<Action>,
+    pressed: Option<Binding>,
+) {
+    if let (Some(action), Some(binding)) = (*rebinding, pressed) {
+        bindings.rebind(action, binding);
+        *rebinding = None;
+    }
+
+    egui::Grid::new("bindings_grid")
+        .num_columns(3)
+        .spacing([20.0, 8.0])
+        .show(ui, |ui| {
+            for action in Action::ALL {
+                ui.label(texts.get(action.key()));
+
+                let described: Vec<String> = bindings
+                    .get(action)
+                    .iter()
+                    .map(|binding| binding.describe(texts))
+                    .collect();
+                ui.label(described.join(", "));
+
+                if *rebinding == Some(action) {
+                    if ui.button(texts.get("settings.press_key")).clicked() {
+                        *rebinding = None;
+                    }
+                } else if ui.button(texts.get("settings.rebind")).clicked() {
+                    *rebinding = Some(action);
+                }
+                ui.end_row();
+            }
+        });
+}
@@ -0,0 +1,221 @@
+use bevy::{
+    asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset},
+    ecs::system::SystemParam,
+    prelude::*,
+    reflect::TypeUuid,
+    utils::HashMap,
+};
+use bevy_asset_loader::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::settings::Settings;
+
+// Keys missing from the chosen language are looked up here, then shown as they are
+pub const FALLBACK_LANGUAGE: &str = "en";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Arg {
+    Text(String),
+    Number(i32),
+    Money(i32),
+    Message(Message),
+}
+
+impl From<i32> for Arg {
+    fn from(number: i32) -> Self {
+        Arg::Number(number)
+    }
+}
+
+impl From<Message> for Arg {
+    fn from(message: Message) -> Self {
+        Arg::Message(message)
+    }
+}
+
+// A key into the string tables with the values for its `{placeholders}`. It is only translated
+// when shown, so that the news feed and saves follow the language setting.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Message {
+    pub key: String,
+    // Which of the key's variants to show, taken modulo their number
+    #[serde(default)]
+    pub variant: u32,
+    #[serde(default)]
+    pub args: Vec<(String, Arg)>,
+    // Sentences shown after this one, separated by a space
+    #[serde(default)]
+    pub then: Vec<Message>,
+}
+
+impl Message {
+    pub fn new(key: impl Into<String>) -> Message {
+        Message {
+            key: key.into(),
+            variant: 0,
+            args: Vec::new(),
+            then: Vec::new(),
+        }
+    }
+
+    // The variant is chosen here rather than when translating, so the seeded game does not
+    // depend on the language
+    pub fn pick(key: impl Into<String>, rng: &mut impl Rng) -> Message {
+        Message {
+            variant: rng.gen(),
+            ..Message::new(key)
+        }
+    }
+
+    pub fn with(mut self, name: &str, arg: impl Into<Arg>) -> Message {
+        self.args.push((name.to_string(), arg.into()));
+        self
+    }
+
+    pub fn then(mut self, next: Message) -> Message {
+        self.then.push(next);
+        self
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Entry {
+    Text(String),
+    Variants(Vec<String>),
+}
+
+#[derive(Deserialize, TypeUuid)]
+#[uuid = "5e0b7d3a-2c8f-4a61-b9e4-7f1d06a3c852"]
+pub struct Locale {
+    pub code: String,
+    // The name of the language in itself, for the language selector
+    pub name: String,
+    // How an amount of money is written, e.g. "${amount}"
+    money: String,
+    strings: HashMap<String, Entry>,
+}
+
+impl Locale {
+    fn text(&self, key: &str, variant: u32) -> Option<&str> {
+        match self.strings.get(key)? {
+            Entry::Text(text) => Some(text.as_str()),
+            Entry::Variants(variants) if variants.is_empty() => None,
+            Entry::Variants(variants) => Some(&variants[variant as usize % variants.len()]),
+        }
+    }
+}
+
+#[derive(Resource, AssetCollection)]
+pub struct Locales {
+    #[asset(
+        paths("locales/en.locale.ron", "locales/de.locale.ron"),
+        collection(typed)
+    )]
+    pub handles: Vec<Handle<Locale>>,
+}
+
+// Translates keys and messages into the language chosen in the settings
+#[derive(SystemParam)]
+pub struct Texts<'w> {
+    settings: Res<'w, Settings>,
+    // Only there once the main menu has loaded
+    locales: Option<Res<'w, Locales>>,
+    assets: Res<'w, Assets<Locale>>,
+}
+
+impl<'w> Texts<'w> {
+    pub fn languages(&self) -> Vec<&Locale> {
+        let Some(locales) = &self.locales else { return Vec::new() };
+
+        locales
+            .handles
+            .iter()
+            .filter_map(|handle| self.assets.get(handle))
+            .collect()
+    }
+
+    fn locale(&self, code: &str) -> Option<&Locale> {
+        self.languages()
+            .into_iter()
+            .find(|locale| locale.code == code)
+    }
+
+    fn lookup<T>(&self, find: impl Fn(&Locale) -> Option<T>) -> Option<T> {
+        self.locale(&self.settings.language)
+            .and_then(&find)
+            .or_else(|| self.locale(FALLBACK_LANGUAGE).and_then(&find))
+    }
+
+    pub fn get(&self, key: &str) -> String {
+        self.message(&Message::new(key))
+    }
+
+    // The sign goes in front of the currency, "-$200" rather than "$-200"
+    pub fn money(&self, amount: i32) -> String {
+        let sign = if amount < 0 { "-" } else { "" };
+        let amount = amount.unsigned_abs();
+        let text = self
+            .lookup(|locale| Some(locale.money.replace("{amount}", &amount.to_string())))
+            .unwrap_or_else(|| format!("${amount}"));
+        format!("{sign}{text}")
+    }
+
+    pub fn message(&self, message: &Message) -> String {
+        let mut text = self
+            .lookup(|locale| {
+                locale
+                    .text(&message.key, message.variant)
+                    .map(str::to_string)
+            })
+            .unwrap_or_else(|| message.key.clone());
+
+        for (name, arg) in message.args.iter() {
+            let value = match arg {
+                Arg::Text(value) => value.clone(),
+                Arg::Number(number) => number.to_string(),
+                Arg::Money(amount) => self.money(*amount),
+                Arg::Message(inner) => self.message(inner),
+            };
+            text = text.replace(&format!("{{{name}}}"), &value);
+        }
+
+        for next in message.then.iter() {
+            text.push(' ');
+            text += &self.message(next);
+        }
+
+        text
+    }
+}
+
+#[derive(Default)]
+pub struct LocaleLoader;
+
+impl AssetLoader for LocaleLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let locale: Locale = ron::de::from_bytes(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(locale));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["locale.ron"]
+    }
+}
+
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<Locale>()
+            .init_asset_loader::<LocaleLoader>();
+    }
+}
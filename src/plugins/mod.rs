@@ -1 +1,6 @@
+pub mod audio;
+pub mod capture;
+pub mod input;
+pub mod locale;
 pub mod post_process;
+pub mod settings;
@@ -1,70 +1,243 @@
+use std::ops::RangeInclusive;
+
 use bevy::{
     ecs::query::QuerySingleError,
     prelude::*,
     reflect::TypeUuid,
     render::{
         camera::RenderTarget,
-        mesh::MeshVertexBufferLayout,
         render_resource::{
-            AsBindGroup, Extent3d, RenderPipelineDescriptor, ShaderRef,
-            SpecializedMeshPipelineError, TextureDescriptor, TextureDimension, TextureFormat,
+            AsBindGroup, Extent3d, ShaderRef, TextureDescriptor, TextureDimension, TextureFormat,
             TextureUsages,
         },
-        texture::BevyDefault,
+        texture::{BevyDefault, ImageSampler},
         view::RenderLayers,
     },
-    sprite::{Material2d, Material2dKey, Material2dPlugin, MaterialMesh2dBundle, Mesh2dHandle},
-    window::WindowResized,
+    sprite::{Material2d, Material2dPlugin, MaterialMesh2dBundle},
 };
+use serde::{Deserialize, Serialize};
 
 #[derive(Component)]
 pub struct PostProcessCamera;
 
+pub const RENDER_SCALES: RangeInclusive<f32> = 0.25..=2.0;
+
+// How the offscreen image is stretched over the window
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Serialize, Deserialize)]
+pub enum Upscaling {
+    // Sharp pixels
+    Nearest,
+    #[default]
+    Linear,
+}
+
+impl Upscaling {
+    fn sampler(&self) -> ImageSampler {
+        match self {
+            Upscaling::Nearest => ImageSampler::nearest(),
+            Upscaling::Linear => ImageSampler::linear(),
+        }
+    }
+}
+
+// The resolution the game is rendered at, relative to the window
+#[derive(Clone, Copy, PartialEq, Resource)]
+pub struct RenderScale {
+    pub scale: f32,
+    pub upscaling: Upscaling,
+}
+
+impl Default for RenderScale {
+    fn default() -> Self {
+        RenderScale {
+            scale: 1.0,
+            upscaling: default(),
+        }
+    }
+}
+
+impl RenderScale {
+    fn clamped(&self) -> f32 {
+        let (min, max) = RENDER_SCALES.into_inner();
+        self.scale.clamp(min, max)
+    }
+
+    // In physical pixels, so a HiDPI display gets its full resolution at a scale of 1
+    fn target_size(&self, physical_size: UVec2) -> Extent3d {
+        let size = (physical_size.as_vec2() * self.clamped())
+            .as_uvec2()
+            .max(UVec2::ONE);
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            ..default()
+        }
+    }
+
+    // Maps a cursor position in the window, in logical pixels, to the same spot on the offscreen
+    // image
+    pub fn target_position(&self, window: &Window, position: Vec2) -> Vec2 {
+        position * window.scale_factor() as f32 * self.clamped()
+    }
+}
+
 #[derive(Default, Resource)]
 pub struct PostProcessConfig {
     pub material_handle: Handle<PostProcessingMaterial>,
     pub image_handle: Handle<Image>,
-    pub quad_entity: Option<Entity>,
+    pub quad_handle: Handle<Mesh>,
+    // What the target was last sized for
+    pub physical_size: UVec2,
+    pub scale_factor: f64,
 }
 
-#[derive(AsBindGroup, TypeUuid, Clone)]
-#[uuid = "2fe1c4d9-8d0f-4321-a6eb-2eade51b647c"]
-#[bind_group_data(TrippyKey)]
-pub struct PostProcessingMaterial {
-    #[texture(0)]
-    #[sampler(1)]
-    source_image: Handle<Image>,
-    pub is_trippy: bool,
+// Holds nothing but the quad, so that only the cameras looking at the finished picture see it
+pub fn render_layer() -> RenderLayers {
+    RenderLayers::layer((RenderLayers::TOTAL_LAYERS - 1) as u8)
 }
 
-#[derive(Eq, PartialEq, Hash, Clone)]
-pub struct TrippyKey {
-    is_trippy: bool,
+pub fn physical_size(window: &Window) -> UVec2 {
+    UVec2::new(window.physical_width(), window.physical_height())
 }
 
-impl From<&PostProcessingMaterial> for TrippyKey {
-    fn from(material: &PostProcessingMaterial) -> Self {
-        Self {
-            is_trippy: material.is_trippy,
+// The quad is drawn by a 2d camera working in logical pixels, so it covers the window in those
+fn quad_mesh(window: &Window) -> Mesh {
+    Mesh::from(shape::Quad::new(Vec2::new(window.width(), window.height())))
+}
+
+// The effects the shader knows, in the order they are stacked by default
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PostEffect {
+    // Wobbling, colour swapped channels
+    Trippy,
+    // A swaying blur
+    Nausea,
+    // Darkens the edges of the screen
+    Blackout,
+    // Flickering grain with a sickly tint
+    Radiation,
+    // Dithers down to a small fixed palette
+    Retro,
+    // Stretches contrast and saturation
+    HighContrast,
+    // Shift the colours a viewer with the deficiency cannot tell apart into ones they can
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+}
+
+impl PostEffect {
+    // Must match the ids in `post_process.wgsl`
+    fn shader_id(&self) -> f32 {
+        match self {
+            PostEffect::Trippy => 0.0,
+            PostEffect::Nausea => 1.0,
+            PostEffect::Blackout => 2.0,
+            PostEffect::Radiation => 3.0,
+            PostEffect::HighContrast => 4.0,
+            PostEffect::Protanopia => 5.0,
+            PostEffect::Deuteranopia => 6.0,
+            PostEffect::Tritanopia => 7.0,
+            PostEffect::Retro => 8.0,
         }
     }
 }
 
-impl Material2d for PostProcessingMaterial {
-    fn fragment_shader() -> ShaderRef {
-        "shaders/trippy.wgsl".into()
+// At most this many effects are drawn at once
+const MAX_LAYERS: usize = 8;
+// Seconds an effect takes to fade from nothing to full strength
+const FADE_TIME: f32 = 0.75;
+
+struct Layer {
+    effect: PostEffect,
+    strength: f32,
+    target: f32,
+}
+
+// An ordered stack of screen effects. Each one fades towards the strength it was last given,
+// and is applied on top of the ones before it.
+#[derive(Resource)]
+pub struct PostProcessStack {
+    layers: Vec<Layer>,
+}
+
+impl Default for PostProcessStack {
+    fn default() -> Self {
+        let mut stack = PostProcessStack { layers: Vec::new() };
+        for effect in [
+            PostEffect::Trippy,
+            PostEffect::Nausea,
+            PostEffect::Blackout,
+            PostEffect::Radiation,
+            PostEffect::Retro,
+            // The accessibility filters go last, so they also apply to everything above
+            PostEffect::HighContrast,
+            PostEffect::Protanopia,
+            PostEffect::Deuteranopia,
+            PostEffect::Tritanopia,
+        ] {
+            stack.push(effect);
+        }
+        stack
     }
+}
 
-    fn specialize(
-        descriptor: &mut RenderPipelineDescriptor,
-        _layout: &MeshVertexBufferLayout,
-        key: Material2dKey<Self>,
-    ) -> Result<(), SpecializedMeshPipelineError> {
-        if key.bind_group_data.is_trippy {
-            let fragment = descriptor.fragment.as_mut().unwrap();
-            fragment.shader_defs.push("IS_TRIPPY".into());
+impl PostProcessStack {
+    // Adds an effect on top of the stack, if it is not there yet
+    pub fn push(&mut self, effect: PostEffect) {
+        if self.layer_mut(effect).is_none() {
+            self.layers.push(Layer {
+                effect,
+                strength: 0.0,
+                target: 0.0,
+            });
         }
-        Ok(())
+    }
+
+    fn layer_mut(&mut self, effect: PostEffect) -> Option<&mut Layer> {
+        self.layers.iter_mut().find(|layer| layer.effect == effect)
+    }
+
+    // Fades the effect in or out
+    pub fn fade_to(&mut self, effect: PostEffect, strength: f32) {
+        let Some(layer) = self.layer_mut(effect) else { bevy::log::warn!("{effect:?} is not in the post-processing stack"); return };
+        layer.target = strength;
+    }
+
+    // Shows the effect at the given strength right away
+    pub fn set(&mut self, effect: PostEffect, strength: f32) {
+        let Some(layer) = self.layer_mut(effect) else { bevy::log::warn!("{effect:?} is not in the post-processing stack"); return };
+        layer.target = strength;
+        layer.strength = strength;
+    }
+
+    // The effect id and strength of each visible layer, bottom first. The unused slots stay at
+    // zero strength, which ends the stack in the shader.
+    fn uniform(&self) -> [Vec4; MAX_LAYERS] {
+        let mut uniform = [Vec4::ZERO; MAX_LAYERS];
+
+        let visible = self.layers.iter().filter(|layer| layer.strength > 0.001);
+        for (slot, layer) in uniform.iter_mut().zip(visible) {
+            *slot = Vec4::new(layer.effect.shader_id(), layer.strength, 0.0, 0.0);
+        }
+
+        uniform
+    }
+}
+
+#[derive(AsBindGroup, TypeUuid, Clone)]
+#[uuid = "2fe1c4d9-8d0f-4321-a6eb-2eade51b647c"]
+pub struct PostProcessingMaterial {
+    #[texture(0)]
+    #[sampler(1)]
+    source_image: Handle<Image>,
+    #[uniform(2)]
+    stack: [Vec4; MAX_LAYERS],
+}
+
+impl Material2d for PostProcessingMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/post_process.wgsl".into()
     }
 }
 
@@ -75,14 +248,11 @@ fn setup_post_processing(
     windows: Query<&Window>,
     mut post_processing_materials: ResMut<Assets<PostProcessingMaterial>>,
     mut post_process_config: ResMut<PostProcessConfig>,
+    render_scale: Res<RenderScale>,
 ) {
     let window = windows.single();
 
-    let size = Extent3d {
-        width: window.width() as u32,
-        height: window.height() as u32,
-        ..default()
-    };
+    let size = render_scale.target_size(physical_size(window));
 
     let mut image = Image {
         texture_descriptor: TextureDescriptor {
@@ -97,6 +267,7 @@ fn setup_post_processing(
                 | TextureUsages::RENDER_ATTACHMENT,
             view_formats: &[],
         },
+        sampler_descriptor: render_scale.upscaling.sampler(),
         ..default()
     };
 
@@ -104,32 +275,27 @@ fn setup_post_processing(
 
     let image_handle = images.add(image);
 
-    let render_layer = RenderLayers::layer((RenderLayers::TOTAL_LAYERS - 1) as u8);
+    let render_layer = render_layer();
 
-    let quad_handle = meshes.add(Mesh::from(shape::Quad::new(Vec2::new(
-        size.width as f32,
-        size.height as f32,
-    ))));
+    let quad_handle = meshes.add(quad_mesh(window));
 
     let material_handle = post_processing_materials.add(PostProcessingMaterial {
         source_image: image_handle.clone(),
-        is_trippy: false,
+        stack: [Vec4::ZERO; MAX_LAYERS],
     });
 
-    let quad_entity = commands
-        .spawn((
-            MaterialMesh2dBundle {
-                mesh: quad_handle.into(),
-                material: material_handle.clone(),
-                transform: Transform {
-                    translation: Vec3::new(0.0, 0.0, 1.5),
-                    ..default()
-                },
+    commands.spawn((
+        MaterialMesh2dBundle {
+            mesh: quad_handle.clone().into(),
+            material: material_handle.clone(),
+            transform: Transform {
+                translation: Vec3::new(0.0, 0.0, 1.5),
                 ..default()
             },
-            render_layer,
-        ))
-        .id();
+            ..default()
+        },
+        render_layer,
+    ));
 
     commands.spawn((
         Camera2dBundle {
@@ -145,7 +311,9 @@ fn setup_post_processing(
     *post_process_config = PostProcessConfig {
         material_handle,
         image_handle,
-        quad_entity: Some(quad_entity),
+        quad_handle,
+        physical_size: physical_size(window),
+        scale_factor: window.scale_factor(),
     }
 }
 
@@ -164,45 +332,72 @@ fn setup_post_process_camera(
     camera.target = RenderTarget::Image(post_process_config.image_handle.clone());
 }
 
-#[allow(clippy::too_many_arguments)]
-fn fix_resize(
-    mut commands: Commands,
-    mut ev_resize: EventReader<WindowResized>,
-    post_process_config: Res<PostProcessConfig>,
+// Resizes the image and the quad in place whenever the window, its scale factor or the render
+// scale changed. Comparing against the last size rather than following the resize events copes
+// with several of them in a frame, and with scale factor changes that come without one.
+fn fit_to_window(
+    mut post_process_config: ResMut<PostProcessConfig>,
     mut images: ResMut<Assets<Image>>,
     mut meshes: ResMut<Assets<Mesh>>,
     windows: Query<&Window>,
-    mut image_events: EventWriter<AssetEvent<Image>>,
     mut post_processing_materials: ResMut<Assets<PostProcessingMaterial>>,
+    render_scale: Res<RenderScale>,
 ) {
-    if let Some(ev) = ev_resize.iter().last() {
-        let window = windows.get(ev.window).unwrap();
+    let Ok(window) = windows.get_single() else { return };
 
-        let size = Extent3d {
-            width: window.width() as u32,
-            height: window.height() as u32,
-            ..default()
-        };
+    // A minimised window has no size, the old target is kept until it comes back
+    let size = physical_size(window);
+    if size.x == 0 || size.y == 0 {
+        return;
+    }
 
-        let image = images.get_mut(&post_process_config.image_handle).unwrap();
-        image.resize(size);
+    let unchanged = size == post_process_config.physical_size
+        && window.scale_factor() == post_process_config.scale_factor;
+    if unchanged && !render_scale.is_changed() {
+        return;
+    }
+
+    post_process_config.physical_size = size;
+    post_process_config.scale_factor = window.scale_factor();
 
-        let quad_handle = meshes.add(Mesh::from(shape::Quad::new(Vec2::new(
-            size.width as f32,
-            size.height as f32,
-        ))));
+    // Both send a modified event of their own, which has the render world recreate them
+    if let Some(image) = images.get_mut(&post_process_config.image_handle) {
+        image.resize(render_scale.target_size(size));
+        image.sampler_descriptor = render_scale.upscaling.sampler();
+    }
+    if let Some(quad) = meshes.get_mut(&post_process_config.quad_handle) {
+        *quad = quad_mesh(window);
+    }
 
-        commands
-            .get_entity(post_process_config.quad_entity.unwrap())
-            .unwrap()
-            .remove::<Mesh2dHandle>()
-            .insert(Mesh2dHandle(quad_handle));
+    // The bind group still points at the old texture until the material is touched
+    post_processing_materials.get_mut(&post_process_config.material_handle);
+}
 
-        image_events.send(AssetEvent::Modified {
-            handle: post_process_config.image_handle.clone(),
-        });
-        post_processing_materials.get_mut(&post_process_config.material_handle);
+// Fades the layers and hands them to the shader, touching the material only while something
+// changes
+fn update_stack(
+    time: Res<Time>,
+    mut stack: ResMut<PostProcessStack>,
+    post_process_config: Res<PostProcessConfig>,
+    mut post_processing_materials: ResMut<Assets<PostProcessingMaterial>>,
+) {
+    let step = time.delta_seconds() / FADE_TIME;
+    let fading = stack
+        .layers
+        .iter()
+        .any(|layer| layer.strength != layer.target);
+
+    if fading {
+        for layer in stack.layers.iter_mut() {
+            let delta = (layer.target - layer.strength).clamp(-step, step);
+            layer.strength += delta;
+        }
+    } else if !stack.is_changed() {
+        return;
     }
+
+    let Some(material) = post_processing_materials.get_mut(&post_process_config.material_handle) else { return };
+    material.stack = stack.uniform();
 }
 
 pub struct PostProcessingPlugin;
@@ -210,9 +405,12 @@ pub struct PostProcessingPlugin;
 impl Plugin for PostProcessingPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<PostProcessConfig>()
+            .init_resource::<RenderScale>()
+            .init_resource::<PostProcessStack>()
             .add_startup_system(setup_post_processing)
             .add_system(setup_post_process_camera)
-            .add_system(fix_resize)
+            .add_system(fit_to_window)
+            .add_system(update_stack)
             .add_plugin(Material2dPlugin::<PostProcessingMaterial>::default());
     }
 }
@@ -0,0 +1,499 @@
+use std::{fs, path::PathBuf};
+
+use bevy::{app::AppExit, prelude::*, window::WindowMode};
+use bevy_egui::{
+    egui::{self, Color32, FontFamily, FontId, TextStyle},
+    EguiContexts, EguiSettings,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    input::{self, Action, Binding, Bindings},
+    locale::{Texts, FALLBACK_LANGUAGE},
+    post_process::{PostEffect, PostProcessStack, RenderScale, Upscaling, RENDER_SCALES},
+};
+
+pub const RESOLUTIONS: [(f32, f32); 5] = [
+    (800.0, 600.0),
+    (1024.0, 768.0),
+    (1280.0, 720.0),
+    (1600.0, 900.0),
+    (1920.0, 1080.0),
+];
+
+// The file type that the news of a run are exported to
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Serialize, Deserialize)]
+pub enum ExportFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+// A daltonisation filter over the whole picture
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Serialize, Deserialize)]
+pub enum ColourFilter {
+    #[default]
+    None,
+    Protanopia,
+    Deuteranopia,
+    Tritanopia,
+}
+
+impl ColourFilter {
+    pub const ALL: [ColourFilter; 4] = [
+        ColourFilter::None,
+        ColourFilter::Protanopia,
+        ColourFilter::Deuteranopia,
+        ColourFilter::Tritanopia,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            ColourFilter::None => "settings.colour_filter.none",
+            ColourFilter::Protanopia => "settings.colour_filter.protanopia",
+            ColourFilter::Deuteranopia => "settings.colour_filter.deuteranopia",
+            ColourFilter::Tritanopia => "settings.colour_filter.tritanopia",
+        }
+    }
+}
+
+// The colours of the news feed
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug, Serialize, Deserialize)]
+pub enum NewsTheme {
+    #[default]
+    Classic,
+    // Blue and orange instead of green and red
+    ColourBlind,
+    HighContrast,
+}
+
+pub struct NewsPalette {
+    pub muted: Color32,
+    pub headline: Color32,
+    pub event: Color32,
+    pub good: Color32,
+    pub bad: Color32,
+}
+
+impl NewsTheme {
+    pub const ALL: [NewsTheme; 3] = [
+        NewsTheme::Classic,
+        NewsTheme::ColourBlind,
+        NewsTheme::HighContrast,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            NewsTheme::Classic => "settings.news_theme.classic",
+            NewsTheme::ColourBlind => "settings.news_theme.colour_blind",
+            NewsTheme::HighContrast => "settings.news_theme.high_contrast",
+        }
+    }
+
+    pub fn palette(&self) -> NewsPalette {
+        match self {
+            NewsTheme::Classic => NewsPalette {
+                muted: Color32::GRAY,
+                headline: Color32::GRAY,
+                event: Color32::WHITE,
+                good: Color32::GREEN,
+                bad: Color32::RED,
+            },
+            NewsTheme::ColourBlind => NewsPalette {
+                muted: Color32::GRAY,
+                headline: Color32::from_rgb(0xcc, 0xcc, 0xcc),
+                event: Color32::WHITE,
+                good: Color32::from_rgb(0x56, 0xb4, 0xe9),
+                bad: Color32::from_rgb(0xe6, 0x9f, 0x00),
+            },
+            NewsTheme::HighContrast => NewsPalette {
+                muted: Color32::from_rgb(0xdd, 0xdd, 0xdd),
+                headline: Color32::WHITE,
+                event: Color32::WHITE,
+                good: Color32::from_rgb(0x00, 0xff, 0xff),
+                bad: Color32::from_rgb(0xff, 0xff, 0x00),
+            },
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub master_volume: f64,
+    pub sfx_volume: f64,
+    pub music_volume: f64,
+    pub fullscreen: bool,
+    pub resolution: (f32, f32),
+    pub ui_scale: f64,
+    pub news_font_size: f32,
+    pub bindings: Bindings,
+    pub language: String,
+    pub export_format: ExportFormat,
+    pub colour_filter: ColourFilter,
+    pub high_contrast: bool,
+    pub news_theme: NewsTheme,
+    pub render_scale: f32,
+    pub upscaling: Upscaling,
+    pub retro: bool,
+    pub clip_buffer: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            master_volume: 1.0,
+            sfx_volume: 1.0,
+            music_volume: 1.0,
+            fullscreen: false,
+            resolution: RESOLUTIONS[0],
+            ui_scale: 1.0,
+            news_font_size: 18.0,
+            bindings: default(),
+            language: FALLBACK_LANGUAGE.to_string(),
+            export_format: default(),
+            colour_filter: default(),
+            high_contrast: false,
+            news_theme: default(),
+            render_scale: 1.0,
+            upscaling: default(),
+            retro: false,
+            clip_buffer: false,
+        }
+    }
+}
+
+fn settings_path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("junkmart").join("settings.ron"))
+}
+
+impl Settings {
+    // Falls back to the defaults when there is no settings file yet or it cannot be read
+    pub fn load() -> Settings {
+        let Some(path) = settings_path().filter(|path| path.exists()) else { return default() };
+
+        let result = fs::read_to_string(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|contents| ron::from_str(&contents).map_err(|err| err.to_string()));
+
+        match result {
+            Ok(settings) => settings,
+            Err(err) => {
+                bevy::log::error!("Could not load settings from {}: {err}", path.display());
+                default()
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        let Some(path) = settings_path() else { bevy::log::warn!("No config directory to save settings to"); return };
+
+        let result = ron::ser::to_string_pretty(self, default())
+            .map_err(|err| err.to_string())
+            .and_then(|contents| {
+                fs::create_dir_all(path.parent().unwrap()).map_err(|err| err.to_string())?;
+                fs::write(&path, contents).map_err(|err| err.to_string())
+            });
+
+        if let Err(err) = result {
+            bevy::log::error!("Could not save settings to {}: {err}", path.display());
+        }
+    }
+
+    pub fn window_mode(&self) -> WindowMode {
+        if self.fullscreen {
+            WindowMode::BorderlessFullscreen
+        } else {
+            WindowMode::Windowed
+        }
+    }
+}
+
+// Whether the settings window is shown, over the main menu or the pause menu
+#[derive(Default, Resource)]
+pub struct SettingsWindow {
+    pub open: bool,
+}
+
+fn apply_settings(
+    settings: Res<Settings>,
+    mut applied: Local<Option<Settings>>,
+    mut windows: Query<&mut Window>,
+    mut egui_settings: ResMut<EguiSettings>,
+    mut contexts: EguiContexts,
+    mut post_process_stack: ResMut<PostProcessStack>,
+    mut render_scale: ResMut<RenderScale>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    // Only touch the window when its settings changed, so that moving a slider keeps a resized window
+    let previous = applied.replace(settings.clone());
+    let window_unchanged = matches!(previous, Some(previous)
+        if previous.fullscreen == settings.fullscreen && previous.resolution == settings.resolution);
+    if !window_unchanged {
+        for mut window in windows.iter_mut() {
+            window.mode = settings.window_mode();
+            window
+                .resolution
+                .set(settings.resolution.0, settings.resolution.1);
+        }
+    }
+
+    egui_settings.scale_factor = settings.ui_scale;
+
+    let ctx = contexts.ctx_mut();
+    let mut style = (*ctx.style()).clone();
+    *style.text_styles.get_mut(&TextStyle::Body).unwrap() =
+        FontId::new(settings.news_font_size, FontFamily::Proportional);
+    ctx.set_style(style);
+
+    // Left alone when unchanged, a new scale rebuilds the offscreen image
+    render_scale.set_if_neq(RenderScale {
+        scale: settings.render_scale,
+        upscaling: settings.upscaling,
+    });
+    let retro = if settings.retro { 1.0 } else { 0.0 };
+    post_process_stack.set(PostEffect::Retro, retro);
+
+    let high_contrast = if settings.high_contrast { 1.0 } else { 0.0 };
+    post_process_stack.set(PostEffect::HighContrast, high_contrast);
+    for (filter, effect) in [
+        (ColourFilter::Protanopia, PostEffect::Protanopia),
+        (ColourFilter::Deuteranopia, PostEffect::Deuteranopia),
+        (ColourFilter::Tritanopia, PostEffect::Tritanopia),
+    ] {
+        let strength = if settings.colour_filter == filter {
+            1.0
+        } else {
+            0.0
+        };
+        post_process_stack.set(effect, strength);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn settings_window(
+    mut commands: Commands,
+    mut contexts: EguiContexts,
+    mut settings_window: ResMut<SettingsWindow>,
+    settings: Res<Settings>,
+    texts: Texts,
+    mut rebinding: Local<Option<Action>>,
+    keys: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    buttons: Res<Input<GamepadButton>>,
+) {
+    if !settings_window.open {
+        *rebinding = None;
+        return;
+    }
+
+    // Edit a copy so that the settings are only marked as changed when something changed
+    let mut edited = settings.clone();
+    let ctx = contexts.ctx_mut();
+
+    // Clicks on the window itself are for its buttons, mouse buttons are bound outside of it
+    let pressed = input::pressed_binding(&keys, &mouse, &buttons)
+        .filter(|binding| !matches!(binding, Binding::Mouse(_)) || !ctx.is_pointer_over_area());
+
+    egui::Window::new(texts.get("settings.title"))
+        .id(egui::Id::new("settings"))
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            egui::Grid::new("settings_grid")
+                .num_columns(2)
+                .spacing([20.0, 8.0])
+                .show(ui, |ui| {
+                    ui.label(texts.get("settings.language"));
+                    egui::ComboBox::from_id_source("language")
+                        .selected_text(
+                            texts
+                                .languages()
+                                .into_iter()
+                                .find(|locale| locale.code == edited.language)
+                                .map_or(edited.language.clone(), |locale| locale.name.clone()),
+                        )
+                        .show_ui(ui, |ui| {
+                            for locale in texts.languages() {
+                                ui.selectable_value(
+                                    &mut edited.language,
+                                    locale.code.clone(),
+                                    &locale.name,
+                                );
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label(texts.get("settings.master_volume"));
+                    ui.add(egui::Slider::new(&mut edited.master_volume, 0.0..=1.0));
+                    ui.end_row();
+
+                    ui.label(texts.get("settings.sfx_volume"));
+                    ui.add(egui::Slider::new(&mut edited.sfx_volume, 0.0..=1.0));
+                    ui.end_row();
+
+                    ui.label(texts.get("settings.music_volume"));
+                    ui.add(egui::Slider::new(&mut edited.music_volume, 0.0..=1.0));
+                    ui.end_row();
+
+                    ui.label(texts.get("settings.fullscreen"));
+                    ui.checkbox(&mut edited.fullscreen, "");
+                    ui.end_row();
+
+                    ui.label(texts.get("settings.resolution"));
+                    ui.add_enabled_ui(!edited.fullscreen, |ui| {
+                        egui::ComboBox::from_id_source("resolution")
+                            .selected_text(format!(
+                                "{}x{}",
+                                edited.resolution.0, edited.resolution.1
+                            ))
+                            .show_ui(ui, |ui| {
+                                for resolution in RESOLUTIONS {
+                                    ui.selectable_value(
+                                        &mut edited.resolution,
+                                        resolution,
+                                        format!("{}x{}", resolution.0, resolution.1),
+                                    );
+                                }
+                            });
+                    });
+                    ui.end_row();
+
+                    ui.label(texts.get("settings.ui_scale"));
+                    ui.add(egui::Slider::new(&mut edited.ui_scale, 0.5..=2.0));
+                    ui.end_row();
+
+                    ui.label(texts.get("settings.render_scale"));
+                    ui.add(egui::Slider::new(&mut edited.render_scale, RENDER_SCALES));
+                    ui.end_row();
+
+                    ui.label(texts.get("settings.upscaling"));
+                    ui.horizontal(|ui| {
+                        ui.radio_value(
+                            &mut edited.upscaling,
+                            Upscaling::Nearest,
+                            texts.get("settings.upscaling.nearest"),
+                        );
+                        ui.radio_value(
+                            &mut edited.upscaling,
+                            Upscaling::Linear,
+                            texts.get("settings.upscaling.linear"),
+                        );
+                    });
+                    ui.end_row();
+
+                    ui.label(texts.get("settings.retro"));
+                    ui.checkbox(&mut edited.retro, "");
+                    ui.end_row();
+
+                    ui.label(texts.get("settings.news_font_size"));
+                    ui.add(egui::Slider::new(&mut edited.news_font_size, 12.0..=32.0));
+                    ui.end_row();
+
+                    ui.label(texts.get("settings.export_format"));
+                    ui.horizontal(|ui| {
+                        ui.radio_value(
+                            &mut edited.export_format,
+                            ExportFormat::Markdown,
+                            "Markdown",
+                        );
+                        ui.radio_value(&mut edited.export_format, ExportFormat::Html, "HTML");
+                    });
+                    ui.end_row();
+
+                    ui.label(texts.get("settings.colour_filter"));
+                    egui::ComboBox::from_id_source("colour_filter")
+                        .selected_text(texts.get(edited.colour_filter.key()))
+                        .show_ui(ui, |ui| {
+                            for filter in ColourFilter::ALL {
+                                ui.selectable_value(
+                                    &mut edited.colour_filter,
+                                    filter,
+                                    texts.get(filter.key()),
+                                );
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label(texts.get("settings.high_contrast"));
+                    ui.checkbox(&mut edited.high_contrast, "");
+                    ui.end_row();
+
+                    ui.label(texts.get("settings.clip_buffer"));
+                    ui.checkbox(&mut edited.clip_buffer, "");
+                    ui.end_row();
+
+                    ui.label(texts.get("settings.news_theme"));
+                    egui::ComboBox::from_id_source("news_theme")
+                        .selected_text(texts.get(edited.news_theme.key()))
+                        .show_ui(ui, |ui| {
+                            for theme in NewsTheme::ALL {
+                                ui.selectable_value(
+                                    &mut edited.news_theme,
+                                    theme,
+                                    texts.get(theme.key()),
+                                );
+                            }
+                        });
+                    ui.end_row();
+                });
+
+            ui.add_space(10.0);
+            ui.collapsing(texts.get("settings.controls"), |ui| {
+                input::bindings_ui(ui, &texts, &mut edited.bindings, &mut rebinding, pressed);
+            });
+
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                if ui.button(texts.get("settings.defaults")).clicked() {
+                    // The language is not reset along with the rest, it might have been picked by mistake
+                    edited = Settings {
+                        language: edited.language.clone(),
+                        ..default()
+                    };
+                }
+                if ui.button(texts.get("settings.done")).clicked() {
+                    settings_window.open = false;
+                }
+            });
+        });
+
+    // Inserted rather than written in place, as the translations above read the settings too
+    if edited != *settings {
+        commands.insert_resource(edited);
+    }
+}
+
+// Writes changed settings once the settings window is closed, however that happened, or when the
+// game exits with it open. Not on every change, as dragging a slider changes them every frame.
+fn persist_settings(
+    settings: Res<Settings>,
+    settings_window: Res<SettingsWindow>,
+    mut unsaved: Local<bool>,
+    ev_exit: EventReader<AppExit>,
+) {
+    // The settings loaded at startup are already on disk
+    if settings.is_changed() && !settings.is_added() {
+        *unsaved = true;
+    }
+
+    if *unsaved && (!settings_window.open || !ev_exit.is_empty()) {
+        settings.save();
+        *unsaved = false;
+    }
+}
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SettingsWindow>()
+            .add_system(apply_settings)
+            .add_system(settings_window)
+            .add_system(persist_settings.after(settings_window));
+    }
+}
@@ -5,30 +5,52 @@ use bevy_egui::{
     EguiContexts,
 };
 
-use super::GameState;
+use crate::plugins::{
+    locale::{Locales, Texts},
+    settings::SettingsWindow,
+};
+
+use super::{
+    play::save::{self, SaveData},
+    GameState,
+};
 
 mod resources;
 
-use resources::AssetList;
+use resources::{AssetList, ContinueFailed, HasSave};
 
 pub struct MainMenuPlugin;
 
 impl Plugin for MainMenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system(ui.in_set(OnUpdate(GameState::MainMenu)))
+        app.add_system(check_save.in_schedule(OnEnter(GameState::MainMenu)))
+            .add_system(ui.in_set(OnUpdate(GameState::MainMenu)))
             .add_loading_state(
                 LoadingState::new(GameState::LoadMainMenu).continue_to_state(GameState::MainMenu),
             )
-            .add_collection_to_loading_state::<_, AssetList>(GameState::LoadMainMenu);
+            .add_collection_to_loading_state::<_, AssetList>(GameState::LoadMainMenu)
+            .add_collection_to_loading_state::<_, Locales>(GameState::LoadMainMenu);
     }
 }
 
+// A run may have been saved or lost since the menu was last shown
+fn check_save(mut commands: Commands) {
+    commands.insert_resource(HasSave(save::has_save()));
+    commands.insert_resource(ContinueFailed::default());
+}
+
+#[allow(clippy::too_many_arguments)]
 fn ui(
+    mut commands: Commands,
     mut contexts: EguiContexts,
     mut exit: EventWriter<AppExit>,
     mut next_state: ResMut<NextState<GameState>>,
     mut image: Local<Option<TextureId>>,
     asset_list: Res<AssetList>,
+    mut has_save: ResMut<HasSave>,
+    mut continue_failed: ResMut<ContinueFailed>,
+    mut settings_window: ResMut<SettingsWindow>,
+    texts: Texts,
 ) {
     if image.is_none() {
         *image = Some(contexts.add_image(asset_list.mainmenu_image.clone_weak()));
@@ -39,14 +61,14 @@ fn ui(
     CentralPanel::default().show(ctx, |ui| {
         ui.with_layout(Layout::top_down(Align::Center), |ui| {
             ui.heading(
-                RichText::new("Welcome to Junk-Mart")
+                RichText::new(texts.get("menu.title"))
                     .size(63.0)
                     .color(Color32::LIGHT_BLUE),
             );
-            ui.label(RichText::new("Where dreams come to die").strikethrough());
-            ui.label(RichText::new("Where we fnid the itmespbb").strikethrough());
+            ui.label(RichText::new(texts.get("menu.tagline.1")).strikethrough());
+            ui.label(RichText::new(texts.get("menu.tagline.2")).strikethrough());
             ui.label(
-                RichText::new("We will find what you ask, or die trying!")
+                RichText::new(texts.get("menu.tagline.3"))
                     .size(21.0)
                     .color(Color32::LIGHT_BLUE),
             );
@@ -56,20 +78,66 @@ fn ui(
 
         ui.with_layout(Layout::bottom_up(Align::Center), |ui| {
             if ui
-                .add(Label::new(RichText::new("EXIT").size(63.0)).sense(Sense::click()))
-                .on_hover_text("WHY THO")
+                .add(
+                    Label::new(RichText::new(texts.get("menu.exit")).size(63.0))
+                        .sense(Sense::click()),
+                )
+                .on_hover_text(texts.get("menu.exit.hover"))
                 .clicked()
             {
                 exit.send(AppExit);
             }
 
+            ui.add_space(20.0);
+            if ui
+                .add(
+                    Label::new(RichText::new(texts.get("menu.settings")).size(42.0))
+                        .sense(Sense::click()),
+                )
+                .clicked()
+            {
+                settings_window.open = true;
+            }
+
             ui.add_space(50.0);
             if ui
-                .add(Label::new(RichText::new("START").size(63.0)).sense(Sense::click()))
+                .add(
+                    Label::new(RichText::new(texts.get("menu.start")).size(63.0))
+                        .sense(Sense::click()),
+                )
                 .clicked()
             {
                 next_state.set(GameState::LoadPlay);
             }
+
+            if has_save.0 {
+                ui.add_space(20.0);
+                if ui
+                    .add(
+                        Label::new(RichText::new(texts.get("menu.continue")).size(63.0))
+                            .sense(Sense::click()),
+                    )
+                    .clicked()
+                {
+                    if let Some(data) = SaveData::load() {
+                        commands.insert_resource(data);
+                        next_state.set(GameState::LoadPlay);
+                    } else {
+                        save::remove_save();
+                        has_save.0 = false;
+                        continue_failed.0 = true;
+                    }
+                }
+            }
+
+            if continue_failed.0 {
+                ui.add_space(20.0);
+                ui.label(
+                    RichText::new(texts.get("menu.continue.failed"))
+                        .size(21.0)
+                        .color(Color32::LIGHT_RED),
+                );
+            }
         })
     });
 }
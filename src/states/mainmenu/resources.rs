@@ -6,3 +6,9 @@ pub struct AssetList {
     #[asset(path = "images/mainmenu.png")]
     pub mainmenu_image: Handle<Image>,
 }
+
+#[derive(Resource)]
+pub struct HasSave(pub bool);
+// Set when the save picked with Continue could not be read and was discarded
+#[derive(Default, Resource)]
+pub struct ContinueFailed(pub bool);
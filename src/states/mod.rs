@@ -3,6 +3,8 @@ use bevy::prelude::*;
 mod mainmenu;
 mod play;
 
+pub use play::balance;
+
 #[derive(States, Default, Clone, Eq, PartialEq, Debug, Hash)]
 pub enum GameState {
     #[default]
@@ -10,6 +12,14 @@ pub enum GameState {
     MainMenu,
     LoadPlay,
     Play,
+    GameOver,
+}
+
+#[derive(States, Default, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum PauseState {
+    #[default]
+    Running,
+    Paused,
 }
 
 pub struct StatePlugin;
@@ -17,6 +27,7 @@ pub struct StatePlugin;
 impl Plugin for StatePlugin {
     fn build(&self, app: &mut App) {
         app.add_state::<GameState>()
+            .add_state::<PauseState>()
             .add_plugin(mainmenu::MainMenuPlugin)
             .add_plugin(play::PlayPlugin);
     }
@@ -0,0 +1,142 @@
+use std::path::PathBuf;
+
+use bevy::{app::AppExit, prelude::*};
+use rand::prelude::*;
+
+use super::{
+    resources::{GameRng, Money},
+    simulation::{LosingConditions, Simulation},
+    status_effects::StatusEffect,
+    utils::{self, item::ItemCatalogue, layout::LayoutPresets},
+};
+
+// Chance that the simulated player remembers where the requested item is
+const RECALL_CHANCE: f64 = 0.5;
+
+#[derive(Resource)]
+struct BalanceRun {
+    catalogue: ItemCatalogue,
+    turns: i32,
+    played: i32,
+    lowest_money: Money,
+}
+
+pub fn turns_from_args() -> Option<i32> {
+    utils::cli_arg("--simulate")
+}
+
+// Where the game finds its assets: BEVY_ASSET_ROOT or the crate when run through cargo, next to
+// the executable otherwise
+fn asset_root() -> PathBuf {
+    let base = std::env::var_os("BEVY_ASSET_ROOT")
+        .or_else(|| std::env::var_os("CARGO_MANIFEST_DIR"))
+        .map(PathBuf::from)
+        .or_else(|| Some(std::env::current_exe().ok()?.parent()?.to_path_buf()))
+        .unwrap_or_default();
+    base.join("assets")
+}
+
+fn load_asset<T>(
+    name: &str,
+    parse: impl FnOnce(&[u8]) -> Result<T, bevy::asset::Error>,
+) -> Result<T, String> {
+    let path = asset_root().join(name);
+    let bytes =
+        std::fs::read(&path).map_err(|err| format!("Could not read {}: {err}", path.display()))?;
+    parse(&bytes).map_err(|err| format!("Invalid {}: {err}", path.display()))
+}
+
+// Plays `turns` turns without a window, GPU or audio and prints a summary, e.g.
+// `cargo run -- --simulate 1000 --seed 42`
+pub fn run(turns: i32) -> Result<(), String> {
+    let catalogue = load_asset("items.catalogue.ron", ItemCatalogue::from_ron)?;
+    let layouts = load_asset("boards.layouts.ron", LayoutPresets::from_ron)?;
+
+    let mut simulation = Simulation {
+        losing_conditions: LosingConditions::from_args(),
+        ..default()
+    };
+    simulation.stock(&catalogue, &layouts.chosen());
+
+    App::new()
+        .add_plugins(MinimalPlugins)
+        .insert_resource(simulation)
+        .init_resource::<GameRng>()
+        .insert_resource(BalanceRun {
+            catalogue,
+            turns,
+            played: 0,
+            lowest_money: Money::new(i32::MAX),
+        })
+        .add_system(play_turn)
+        .run();
+
+    Ok(())
+}
+
+fn play_turn(
+    mut simulation: ResMut<Simulation>,
+    mut rng: ResMut<GameRng>,
+    mut run: ResMut<BalanceRun>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if run.played >= run.turns {
+        return;
+    }
+
+    let rng_seed = rng.seed;
+    let rng = &mut rng.rng;
+
+    let pos = pick_chest(&simulation, rng);
+
+    // Treats whatever it can whenever that does not mean going into debt
+    for &effect in StatusEffect::ALL {
+        if matches!(simulation.treatment_cost(effect), Some(cost) if simulation.money.value() >= cost.value())
+        {
+            simulation.buy_treatment(effect);
+        }
+    }
+
+    simulation.play_turn(pos, &run.catalogue, rng);
+
+    run.played += 1;
+    if simulation.money.value() < run.lowest_money.value() {
+        run.lowest_money = simulation.money;
+    }
+
+    if run.played == run.turns || simulation.defeat.is_some() {
+        println!(
+            "Seed {}: played {} turns, reached customer {}, final balance {}, lowest balance {}, customers killed: {}, war: {}, win: {}",
+            rng_seed,
+            run.played,
+            simulation.customer_no,
+            simulation.money,
+            run.lowest_money,
+            simulation.customers_killed,
+            simulation.war,
+            simulation.win
+        );
+        if let Some(defeat) = simulation.defeat {
+            println!("Lost: {defeat:?}");
+        }
+        exit.send(AppExit);
+    }
+}
+
+// The simulated player: opens the chest holding the requested item if it remembers where that
+// is, or a random one otherwise
+pub fn pick_chest(simulation: &Simulation, rng: &mut impl Rng) -> (i32, i32) {
+    let mut positions: Vec<(i32, i32)> = simulation.chests.keys().copied().collect();
+    positions.sort();
+
+    let wanted = simulation.requested_item.as_ref().map(|(_, item)| *item);
+    let remembered = positions
+        .iter()
+        .copied()
+        .find(|pos| Some(simulation.chests[pos]) == wanted);
+
+    match remembered {
+        Some(pos) if rng.gen_bool(RECALL_CHANCE) => pos,
+        _ => *positions.choose(rng).unwrap(),
+    }
+}
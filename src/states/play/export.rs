@@ -0,0 +1,242 @@
+use std::{
+    fs,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::plugins::{
+    locale::{Arg, Message, Texts},
+    settings::ExportFormat,
+};
+
+use super::{
+    events::{NewsCategory, NewsLevel},
+    resources::{NewsFeed, RunSummary},
+};
+
+// The story of a run, laid out once and written as either Markdown or HTML
+enum Block {
+    Title(String),
+    Heading(String),
+    Paragraph(String),
+    News {
+        level: NewsLevel,
+        headline: bool,
+        text: String,
+    },
+    Balance(String),
+}
+
+fn news_text(texts: &Texts, message: &Message, delta: Option<i32>) -> String {
+    let text = texts.message(message);
+
+    match delta {
+        Some(delta) if delta < 0 => format!("{text} ({})", texts.money(delta)),
+        Some(delta) => format!("{text} (+{})", texts.money(delta)),
+        None => text,
+    }
+}
+
+fn layout(
+    news_feed: &NewsFeed,
+    seed: u64,
+    summary: Option<&RunSummary>,
+    texts: &Texts,
+) -> Vec<Block> {
+    let mut blocks = vec![Block::Title(texts.message(
+        &Message::new("export.title").with("seed", Arg::Text(seed.to_string())),
+    ))];
+
+    if let Some(summary) = summary {
+        blocks.push(Block::Heading(texts.get("export.outcome")));
+        blocks.push(Block::Paragraph(texts.get(summary.defeat.key())));
+        for (key, arg) in [
+            ("game_over.customer_no", Arg::from(summary.customer_no)),
+            ("game_over.served", Arg::from(summary.customers_served)),
+            ("game_over.killed", Arg::from(summary.customers_killed)),
+            ("game_over.balance", Arg::from(summary.money)),
+        ] {
+            blocks.push(Block::Paragraph(
+                texts.message(&Message::new(key).with("value", arg)),
+            ));
+        }
+    }
+
+    let is_headline =
+        |category: NewsCategory| matches!(category, NewsCategory::Headline | NewsCategory::War);
+
+    let mut entries = news_feed.0.iter().peekable();
+    while let Some(entry) = entries.next() {
+        if !matches!(blocks.last(), Some(Block::News { .. })) {
+            blocks.push(Block::Heading(if entry.turn == 0 {
+                texts.get("export.opening")
+            } else {
+                texts.message(&Message::new("export.turn").with("turn", entry.turn))
+            }));
+        }
+
+        let news = &entry.news;
+        blocks.push(Block::News {
+            level: news.level,
+            headline: is_headline(news.category),
+            text: news_text(
+                texts,
+                &news.message,
+                news.money_delta.map(|delta| delta.value()),
+            ),
+        });
+
+        // The balance closes each customer's section
+        if !matches!(entries.peek(), Some(next) if next.turn == entry.turn) {
+            blocks.push(Block::Balance(texts.message(
+                &Message::new("export.balance").with("balance", entry.balance),
+            )));
+        }
+    }
+
+    let headlines: Vec<_> = news_feed
+        .0
+        .iter()
+        .filter(|entry| is_headline(entry.news.category))
+        .collect();
+    if !headlines.is_empty() {
+        blocks.push(Block::Heading(texts.get("export.headlines")));
+        for entry in headlines {
+            let stamp = texts.message(&Message::new("news.stamp").with("turn", entry.turn));
+            blocks.push(Block::News {
+                level: entry.news.level,
+                headline: true,
+                text: format!("{stamp} {}", texts.message(&entry.news.message)),
+            });
+        }
+    }
+
+    blocks
+}
+
+fn to_markdown(blocks: &[Block]) -> String {
+    let mut out = String::new();
+
+    for block in blocks {
+        match block {
+            Block::Title(text) => out += &format!("# {text}\n"),
+            Block::Heading(text) => out += &format!("\n## {text}\n\n"),
+            Block::Paragraph(text) => out += &format!("{text}\n\n"),
+            Block::News {
+                headline: true,
+                text,
+                ..
+            } => out += &format!("- **{text}**\n"),
+            Block::News { text, .. } => out += &format!("- {text}\n"),
+            Block::Balance(text) => out += &format!("\n*{text}*\n"),
+        }
+    }
+
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn to_html(blocks: &[Block]) -> String {
+    let mut out = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<style>\n\
+         body { font-family: sans-serif; max-width: 50em; margin: auto; }\n\
+         .external { color: gray; } .correct { color: green; } .wrong { color: firebrick; }\n\
+         .headline { font-weight: bold; } .balance { font-style: italic; }\n\
+         </style>\n</head>\n<body>\n",
+    );
+    let mut in_list = false;
+
+    for block in blocks {
+        let is_news = matches!(block, Block::News { .. });
+        if in_list && !is_news {
+            out += "</ul>\n";
+        } else if !in_list && is_news {
+            out += "<ul>\n";
+        }
+        in_list = is_news;
+
+        match block {
+            Block::Title(text) => out += &format!("<h1>{}</h1>\n", escape_html(text)),
+            Block::Heading(text) => out += &format!("<h2>{}</h2>\n", escape_html(text)),
+            Block::Paragraph(text) => out += &format!("<p>{}</p>\n", escape_html(text)),
+            Block::News {
+                level,
+                headline,
+                text,
+            } => {
+                let mut class = match level {
+                    NewsLevel::External => "external",
+                    NewsLevel::Event => "event",
+                    NewsLevel::Correct => "correct",
+                    NewsLevel::Wrong => "wrong",
+                }
+                .to_string();
+                if *headline {
+                    class += " headline";
+                }
+                out += &format!("<li class=\"{class}\">{}</li>\n", escape_html(text));
+            }
+            Block::Balance(text) => {
+                out += &format!("<p class=\"balance\">{}</p>\n", escape_html(text))
+            }
+        }
+    }
+    if in_list {
+        out += "</ul>\n";
+    }
+
+    out += "</body>\n</html>\n";
+    out
+}
+
+// Writes the whole news history of a run to a new file in the data directory and returns its
+// path. The end screen passes the summary of the lost run along.
+pub fn export(
+    news_feed: &NewsFeed,
+    seed: u64,
+    summary: Option<&RunSummary>,
+    format: ExportFormat,
+    texts: &Texts,
+) -> Result<PathBuf, String> {
+    let dir = dirs::data_dir()
+        .ok_or("no data directory")?
+        .join("junkmart")
+        .join("exports");
+
+    let blocks = layout(news_feed, seed, summary, texts);
+    let (contents, extension) = match format {
+        ExportFormat::Markdown => (to_markdown(&blocks), "md"),
+        ExportFormat::Html => (to_html(&blocks), "html"),
+    };
+
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or_default();
+    let path = dir.join(format!("run-{seed}-{time}.{extension}"));
+
+    fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
+    fs::write(&path, contents).map_err(|err| err.to_string())?;
+
+    bevy::log::debug!("Exported the news to {}", path.display());
+    Ok(path)
+}
+
+// What the menus show after exporting
+pub fn outcome(result: &Result<PathBuf, String>) -> Message {
+    match result {
+        Ok(path) => {
+            Message::new("export.saved").with("path", Arg::Text(path.display().to_string()))
+        }
+        Err(err) => {
+            bevy::log::error!("Could not export the news: {err}");
+            Message::new("export.failed").with("error", Arg::Text(err.clone()))
+        }
+    }
+}
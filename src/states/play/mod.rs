@@ -1,9 +1,14 @@
 use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
 
-use super::GameState;
+use super::{GameState, PauseState};
 
+pub mod balance;
+mod export;
 mod resources;
+pub mod save;
+mod simulation;
+mod status_effects;
 mod systems;
 mod utils;
 
@@ -14,9 +19,19 @@ mod components {
     pub struct Rotate;
     #[derive(Component)]
     pub struct PointerLight;
+    // Despawned when leaving the Play state
+    #[derive(Component)]
+    pub struct PlayScoped;
 }
 
 mod events {
+    use serde::{Deserialize, Serialize};
+
+    use crate::plugins::locale::Message;
+
+    use super::resources::Money;
+
+    #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
     pub enum NewsLevel {
         External,
         Event,
@@ -24,19 +39,105 @@ mod events {
         Wrong,
     }
 
-    pub struct NewsFeedUpdate(pub NewsLevel, pub String);
+    impl NewsLevel {
+        pub const ALL: [NewsLevel; 4] = [
+            NewsLevel::External,
+            NewsLevel::Event,
+            NewsLevel::Correct,
+            NewsLevel::Wrong,
+        ];
+
+        pub fn key(&self) -> &'static str {
+            match self {
+                NewsLevel::External => "news.level.external",
+                NewsLevel::Event => "news.level.event",
+                NewsLevel::Correct => "news.level.correct",
+                NewsLevel::Wrong => "news.level.wrong",
+            }
+        }
+
+        // Tells the levels apart without relying on colour
+        pub fn marker(&self) -> &'static str {
+            match self {
+                NewsLevel::External => "📰",
+                NewsLevel::Event => "ℹ",
+                NewsLevel::Correct => "✔",
+                NewsLevel::Wrong => "✖",
+            }
+        }
+    }
+
+    // What a piece of news is about, independent of how it is coloured
+    #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+    pub enum NewsCategory {
+        Story,
+        Customer,
+        Sale,
+        Mishap,
+        Condition,
+        Headline,
+        War,
+    }
+
+    #[derive(Clone, Serialize, Deserialize)]
+    pub struct NewsFeedUpdate {
+        pub level: NewsLevel,
+        pub category: NewsCategory,
+        pub message: Message,
+        // Money gained or lost by what the news reports
+        pub money_delta: Option<Money>,
+    }
+
+    impl NewsFeedUpdate {
+        pub fn new(level: NewsLevel, category: NewsCategory, message: Message) -> Self {
+            NewsFeedUpdate {
+                level,
+                category,
+                message,
+                money_delta: None,
+            }
+        }
+
+        pub fn with_money(self, delta: Money) -> Self {
+            NewsFeedUpdate {
+                money_delta: Some(delta),
+                ..self
+            }
+        }
+    }
+
+    pub struct SaveGame;
+
+    // Turns the background music down for the given number of seconds
+    pub struct DuckMusic(pub f32);
 }
 
+// Systems that advance the game, frozen while the pause menu is open
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Unpaused;
+
 pub struct PlayPlugin;
 
 impl Plugin for PlayPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<events::NewsFeedUpdate>()
+            .add_event::<events::SaveGame>()
+            .add_event::<events::DuckMusic>()
+            .configure_set(
+                Unpaused
+                    .in_set(OnUpdate(GameState::Play))
+                    .run_if(in_state(PauseState::Running)),
+            )
+            .add_asset::<utils::item::ItemCatalogue>()
+            .init_asset_loader::<utils::item::ItemCatalogueLoader>()
+            .add_asset::<utils::layout::LayoutPresets>()
+            .init_asset_loader::<utils::layout::LayoutPresetsLoader>()
+            .add_asset::<utils::sound_bank::SoundBank>()
+            .init_asset_loader::<utils::sound_bank::SoundBankLoader>()
             .add_loading_state(
                 LoadingState::new(GameState::LoadPlay).continue_to_state(GameState::Play),
             )
-            .add_collection_to_loading_state::<_, resources::AssetList>(GameState::LoadPlay)
-            .add_collection_to_loading_state::<_, resources::SoundList>(GameState::LoadPlay);
+            .add_collection_to_loading_state::<_, resources::AssetList>(GameState::LoadPlay);
 
         resources::init_resources(app);
         systems::add_systems(app);
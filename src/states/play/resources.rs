@@ -1,17 +1,28 @@
 use std::{
     collections::VecDeque,
     fmt::{Display, Formatter},
-    ops::{AddAssign, SubAssign},
+    ops::{AddAssign, Neg, SubAssign},
 };
 
 use bevy::{prelude::*, utils::HashMap};
 use bevy_asset_loader::prelude::*;
-use bevy_egui::egui::RichText;
 use bevy_kira_audio::prelude::*;
+use rand::prelude::*;
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::plugins::locale::{Arg, Message};
 
 use super::{
+    events::NewsFeedUpdate,
+    simulation::{Defeat, Simulation},
     systems::instructions::Instruction,
-    utils::{item::Item, StatusEffect},
+    utils::{
+        self,
+        item::{Item, ItemCatalogue},
+        layout::{Layout, LayoutPresets},
+        sound_bank::{MusicLayer, SoundBank},
+    },
 };
 
 #[derive(Resource, AssetCollection)]
@@ -20,101 +31,64 @@ pub struct AssetList {
     pub level: Handle<Scene>,
     #[asset(path = "objects/chest.glb#Scene0")]
     pub chest: Handle<Scene>,
-    #[asset(path = "objects/barrel.glb#Scene0")]
-    pub barrel: Handle<Scene>,
-    #[asset(path = "objects/burger.glb#Scene0")]
-    pub burger: Handle<Scene>,
-    #[asset(path = "objects/gun.glb#Scene0")]
-    pub gun: Handle<Scene>,
-    #[asset(path = "objects/pill.glb#Scene0")]
-    pub pill: Handle<Scene>,
-    #[asset(path = "objects/screwdriver.glb#Scene0")]
-    pub screwdriver: Handle<Scene>,
-}
-
-pub enum Sound {
-    Death,
-    Eat,
-    Energized,
-    Fart,
-    Flush,
-    Gunshot,
-    LargeHit,
-    SadTrombone,
-    Siren,
-    SmallHit,
-    Strange,
-}
-
-#[derive(Resource, AssetCollection)]
-pub struct SoundList {
-    #[asset(path = "sounds/correct.ogg")]
-    pub correct: Handle<AudioSource>,
-    #[asset(path = "sounds/death.ogg")]
-    pub death: Handle<AudioSource>,
-    #[asset(path = "sounds/eat.ogg")]
-    pub eat: Handle<AudioSource>,
-    #[asset(path = "sounds/energized.ogg")]
-    pub energized: Handle<AudioSource>,
-    #[asset(path = "sounds/fart.ogg")]
-    pub fart: Handle<AudioSource>,
-    #[asset(path = "sounds/flush.ogg")]
-    pub flush: Handle<AudioSource>,
-    #[asset(path = "sounds/gunshot.ogg")]
-    pub gunshot: Handle<AudioSource>,
-    #[asset(path = "sounds/large_hit.ogg")]
-    pub large_hit: Handle<AudioSource>,
-    #[asset(path = "sounds/nuke_siren.ogg")]
-    pub nuke_siren: Handle<AudioSource>,
-    #[asset(path = "sounds/sad_trombone.ogg")]
-    pub sad_trombone: Handle<AudioSource>,
-    #[asset(path = "sounds/siren.ogg")]
-    pub siren: Handle<AudioSource>,
-    #[asset(path = "sounds/small_hit.ogg")]
-    pub small_hit: Handle<AudioSource>,
-    #[asset(path = "sounds/strange.ogg")]
-    pub strange: Handle<AudioSource>,
-    #[asset(path = "sounds/win_music.ogg")]
-    pub win_music: Handle<AudioSource>,
+    #[asset(path = "items.catalogue.ron")]
+    pub items: Handle<ItemCatalogue>,
+    #[asset(path = "boards.layouts.ron")]
+    pub layouts: Handle<LayoutPresets>,
+    #[asset(path = "sounds/cues.bank.ron")]
+    pub sounds: Handle<SoundBank>,
 }
 
 #[derive(Default, Resource)]
-pub struct Chests(pub HashMap<(i32, i32), (Entity, Item)>);
+pub struct Chests(pub HashMap<(i32, i32), Entity>);
 #[derive(Default, Resource)]
 pub struct HoveredChest(pub Option<(i32, i32)>);
 #[derive(Default, Resource)]
 pub struct Instructions(pub VecDeque<Instruction>);
+// Every piece of news of the run, stamped with the customer it arrived with and the balance
+// right after it
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NewsEntry {
+    pub turn: i32,
+    pub balance: Money,
+    pub news: NewsFeedUpdate,
+}
+#[derive(Clone, Default, Resource, Serialize, Deserialize)]
+pub struct NewsFeed(pub Vec<NewsEntry>);
+// What the last export from the pause menu or the end screen came to, shown below its button
 #[derive(Default, Resource)]
-pub struct StatusEffects(pub HashMap<StatusEffect, i32>);
-#[derive(Default, Resource)]
-pub struct NewsFeed(pub VecDeque<RichText>);
+pub struct ExportOutcome(pub Option<Message>);
 #[derive(Default, Resource)]
 pub struct ActiveItem(pub Option<(Item, Entity)>);
-#[derive(Default, Resource)]
-pub struct RequestedItem(pub Option<(String, Item)>);
-#[derive(Default, Resource)]
-pub struct PrevRequestedItem(pub Option<Item>);
-#[derive(Default, Resource)]
-pub struct CustomerNumber(pub i32);
-#[derive(Default, Resource)]
-pub struct GlobalNews(pub VecDeque<String>);
-#[derive(Default, Resource)]
-pub struct WarNews(pub VecDeque<String>);
-#[derive(Default, Resource)]
-pub struct War(pub bool);
-#[derive(Default, Resource)]
-pub struct Win(pub bool);
-#[derive(Clone, Copy, Default, Resource)]
+// What the player wrote down about each chest
+#[derive(Clone, Default, Resource, Serialize, Deserialize)]
+pub struct Notes {
+    pub entries: HashMap<(i32, i32), String>,
+    #[serde(skip)]
+    pub open: bool,
+}
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct Money(i32);
 
 impl Money {
     pub fn new(x: i32) -> Self {
         Money(x)
     }
+
+    pub fn value(&self) -> i32 {
+        self.0
+    }
 }
 impl Display for Money {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "${}", self.0)
+        let sign = if self.0 < 0 { "-" } else { "" };
+        write!(f, "{sign}${}", self.0.unsigned_abs())
+    }
+}
+impl From<Money> for Arg {
+    fn from(money: Money) -> Self {
+        Arg::Money(money.0)
     }
 }
 impl AddAssign<Money> for Money {
@@ -122,25 +96,88 @@ impl AddAssign<Money> for Money {
         self.0 += rhs.0;
     }
 }
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money(-self.0)
+    }
+}
 impl SubAssign<Money> for Money {
     fn sub_assign(&mut self, rhs: Money) {
         self.0 -= rhs.0;
     }
 }
 
+// What the end screen shows about a lost run
+#[derive(Resource)]
+pub struct RunSummary {
+    pub defeat: Defeat,
+    pub seed: u64,
+    pub customer_no: i32,
+    pub customers_served: i32,
+    pub customers_killed: i32,
+    pub money: Money,
+    pub news_feed: NewsFeed,
+}
+
+#[derive(Default, Resource)]
+pub struct MusicState {
+    pub layers: HashMap<MusicLayer, Handle<AudioInstance>>,
+    // The layer and ducking that the layer volumes were last faded to
+    pub applied: Option<(MusicLayer, bool)>,
+    pub duck_remaining: f32,
+}
+
+#[derive(Clone, Resource, Serialize, Deserialize)]
+pub struct GameRng {
+    pub seed: u64,
+    pub rng: ChaCha12Rng,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        GameRng {
+            seed,
+            rng: ChaCha12Rng::seed_from_u64(seed),
+        }
+    }
+}
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(utils::cli_arg("--seed").unwrap_or_else(|| thread_rng().gen()))
+    }
+}
+
+// Each menu opens without the outcome of an export made the last time it was open
+pub fn clear_export_outcome(mut export_outcome: ResMut<ExportOutcome>) {
+    export_outcome.0 = None;
+}
+
+// Puts every play resource back to its initial value, so that the next run starts clean
+pub fn reset_resources(mut commands: Commands) {
+    commands.insert_resource(Chests::default());
+    commands.insert_resource(HoveredChest::default());
+    commands.insert_resource(Instructions::default());
+    commands.insert_resource(NewsFeed::default());
+    commands.insert_resource(ActiveItem::default());
+    commands.insert_resource(Notes::default());
+    commands.insert_resource(MusicState::default());
+    commands.insert_resource(Simulation::default());
+    commands.insert_resource(GameRng::default());
+    commands.insert_resource(Layout::default());
+}
+
 pub fn init_resources(app: &mut App) {
     app.init_resource::<Chests>()
         .init_resource::<HoveredChest>()
         .init_resource::<Instructions>()
-        .init_resource::<StatusEffects>()
         .init_resource::<NewsFeed>()
+        .init_resource::<ExportOutcome>()
         .init_resource::<ActiveItem>()
-        .init_resource::<RequestedItem>()
-        .init_resource::<PrevRequestedItem>()
-        .init_resource::<CustomerNumber>()
-        .init_resource::<GlobalNews>()
-        .init_resource::<WarNews>()
-        .init_resource::<War>()
-        .init_resource::<Win>()
-        .insert_resource::<Money>(Money(1000));
+        .init_resource::<Notes>()
+        .init_resource::<MusicState>()
+        .init_resource::<Simulation>()
+        .init_resource::<GameRng>()
+        .init_resource::<Layout>();
 }
@@ -0,0 +1,319 @@
+use std::{fs, path::PathBuf};
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::plugins::locale::Message;
+
+use super::{
+    events::{NewsCategory, NewsFeedUpdate, NewsLevel, SaveGame},
+    resources::{GameRng, Instructions, NewsFeed, Notes},
+    simulation::Simulation,
+    utils::{
+        item::{Catalogue, Item, ItemCatalogue},
+        layout::Layout,
+    },
+};
+
+// Saves of any other version are discarded instead of continued
+const SAVE_VERSION: u32 = 1;
+
+// The run picked from the main menu, put in place once the catalogue is loaded
+#[derive(Resource, Serialize, Deserialize)]
+pub struct SaveData {
+    version: u32,
+    simulation: Simulation,
+    items: SavedItems,
+    layout: Layout,
+    rng: GameRng,
+    news_feed: NewsFeed,
+    #[serde(default)]
+    notes: Notes,
+}
+
+// The items of the simulation by catalogue id, which stay valid when the catalogue is reordered
+#[derive(Serialize, Deserialize)]
+struct SavedItems {
+    chests: HashMap<(i32, i32), String>,
+    requested_item: Option<(Message, String)>,
+    prev_requested_item: Option<String>,
+}
+
+// Read on its own first, so that an older save is reported as such instead of as unparsable
+#[derive(Deserialize)]
+struct SaveVersion {
+    #[serde(default)]
+    version: u32,
+}
+
+fn save_path() -> Option<PathBuf> {
+    Some(dirs::data_dir()?.join("junkmart").join("save.ron"))
+}
+
+pub fn has_save() -> bool {
+    save_path().map(|path| path.exists()).unwrap_or(false)
+}
+
+// A lost run cannot be continued
+pub fn remove_save() {
+    let Some(path) = save_path().filter(|path| path.exists()) else { return };
+
+    if let Err(err) = fs::remove_file(&path) {
+        bevy::log::error!("Could not remove save file {}: {err}", path.display());
+    }
+}
+
+impl SaveData {
+    pub fn load() -> Option<SaveData> {
+        let path = save_path()?;
+
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) => {
+                bevy::log::error!("Could not read save file {}: {err}", path.display());
+                return None;
+            }
+        };
+
+        match SaveData::parse(&contents) {
+            Ok(data) => Some(data),
+            Err(err) => {
+                bevy::log::error!("Could not load save file {}: {err}", path.display());
+                None
+            }
+        }
+    }
+
+    fn parse(contents: &str) -> Result<SaveData, String> {
+        if let Ok(SaveVersion { version }) = ron::from_str(contents) {
+            if version != SAVE_VERSION {
+                return Err(format!("version {version}, expected {SAVE_VERSION}"));
+            }
+        }
+
+        ron::from_str(contents).map_err(|err| err.to_string())
+    }
+
+    // The saved simulation with its items looked up in the catalogue, as long as the save
+    // fits both the catalogue and its own board
+    fn simulation(&self, catalogue: &ItemCatalogue) -> Result<Simulation, String> {
+        let layout = &self.layout;
+        if layout.columns < 1 || layout.rows < 1 || layout.spacing <= 0.0 {
+            return Err(format!(
+                "the board of {}x{} chests is invalid",
+                layout.columns, layout.rows
+            ));
+        }
+
+        let positions: HashSet<(i32, i32)> = layout.positions().collect();
+        let saved: HashSet<(i32, i32)> = self.items.chests.keys().copied().collect();
+        if saved != positions {
+            return Err("the chests do not match the board".to_string());
+        }
+
+        let item = |id: &String| -> Result<Item, String> {
+            catalogue
+                .item(id)
+                .ok_or_else(|| format!("item \"{id}\" is not in the catalogue"))
+        };
+
+        let mut simulation = self.simulation.clone();
+        for (pos, id) in self.items.chests.iter() {
+            simulation.chests.insert(*pos, item(id)?);
+        }
+        simulation.requested_item = match &self.items.requested_item {
+            Some((message, id)) => Some((message.clone(), item(id)?)),
+            None => None,
+        };
+        simulation.prev_requested_item = match &self.items.prev_requested_item {
+            Some(id) => Some(item(id)?),
+            None => None,
+        };
+
+        Ok(simulation)
+    }
+}
+
+impl SavedItems {
+    fn new(simulation: &Simulation, catalogue: &ItemCatalogue) -> Self {
+        let id = |item: Item| catalogue[item].id.clone();
+
+        SavedItems {
+            chests: simulation
+                .chests
+                .iter()
+                .map(|(pos, item)| (*pos, id(*item)))
+                .collect(),
+            requested_item: simulation
+                .requested_item
+                .as_ref()
+                .map(|(message, item)| (message.clone(), id(*item))),
+            prev_requested_item: simulation.prev_requested_item.map(id),
+        }
+    }
+}
+
+// Puts a run continued from the main menu in place, or discards it with a note in the news
+// when it no longer fits the catalogue
+#[allow(clippy::too_many_arguments)]
+pub fn continue_run(
+    mut commands: Commands,
+    save: Option<Res<SaveData>>,
+    catalogue: Catalogue,
+    mut simulation: ResMut<Simulation>,
+    mut layout: ResMut<Layout>,
+    mut rng: ResMut<GameRng>,
+    mut news_feed: ResMut<NewsFeed>,
+    mut notes: ResMut<Notes>,
+    mut ev_news: EventWriter<NewsFeedUpdate>,
+) {
+    let Some(save) = save else { return };
+    commands.remove_resource::<SaveData>();
+
+    match save.simulation(catalogue.get()) {
+        Ok(restored) => {
+            *simulation = restored;
+            *layout = save.layout.clone();
+            *rng = save.rng.clone();
+            *news_feed = save.news_feed.clone();
+            *notes = save.notes.clone();
+        }
+        Err(err) => {
+            bevy::log::error!("Discarding the saved game: {err}");
+            remove_save();
+            ev_news.send(NewsFeedUpdate::new(
+                NewsLevel::Wrong,
+                NewsCategory::Story,
+                Message::new("news.save_discarded"),
+            ));
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn save_game(
+    mut ev_save: EventReader<SaveGame>,
+    instructions: Res<Instructions>,
+    simulation: Res<Simulation>,
+    layout: Res<Layout>,
+    rng: Res<GameRng>,
+    news_feed: Res<NewsFeed>,
+    notes: Res<Notes>,
+    catalogue: Catalogue,
+) {
+    if ev_save.iter().last().is_none() {
+        return;
+    }
+
+    if !instructions.0.is_empty() {
+        bevy::log::warn!("Refusing to save in the middle of a turn");
+        return;
+    }
+
+    let Some(path) = save_path() else { bevy::log::warn!("No data directory to save to"); return };
+
+    let data = SaveData {
+        version: SAVE_VERSION,
+        simulation: simulation.clone(),
+        items: SavedItems::new(&simulation, catalogue.get()),
+        layout: layout.clone(),
+        rng: rng.clone(),
+        news_feed: news_feed.clone(),
+        notes: notes.clone(),
+    };
+
+    let result = ron::ser::to_string_pretty(&data, default())
+        .map_err(|err| err.to_string())
+        .and_then(|contents| {
+            fs::create_dir_all(path.parent().unwrap()).map_err(|err| err.to_string())?;
+            fs::write(&path, contents).map_err(|err| err.to_string())
+        });
+
+    match result {
+        Ok(()) => bevy::log::debug!("Saved game to {}", path.display()),
+        Err(err) => bevy::log::error!("Could not save game to {}: {err}", path.display()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::states::play::utils::layout::LayoutPresets;
+
+    fn catalogue() -> ItemCatalogue {
+        ItemCatalogue::from_ron(include_bytes!("../../../assets/items.catalogue.ron")).unwrap()
+    }
+
+    fn saved_run(catalogue: &ItemCatalogue) -> String {
+        let presets =
+            LayoutPresets::from_ron(include_bytes!("../../../assets/boards.layouts.ron")).unwrap();
+        let layout = presets.boards[&presets.default].clone();
+        let mut simulation = Simulation::default();
+        simulation.stock(catalogue, &layout);
+        let mut rng = GameRng::new(7);
+        simulation.play_turn((0, 0), catalogue, &mut rng.rng);
+
+        let data = SaveData {
+            version: SAVE_VERSION,
+            items: SavedItems::new(&simulation, catalogue),
+            simulation,
+            layout,
+            rng,
+            news_feed: NewsFeed::default(),
+            notes: Notes::default(),
+        };
+        ron::ser::to_string_pretty(&data, default()).unwrap()
+    }
+
+    #[test]
+    fn saves_outlive_a_reordered_catalogue() {
+        let catalogue = catalogue();
+        let contents = saved_run(&catalogue);
+        let data = SaveData::parse(&contents).unwrap();
+
+        let mut reordered = self::catalogue();
+        reordered.items.reverse();
+        let simulation = data.simulation(&reordered).unwrap();
+
+        for (pos, id) in data.items.chests.iter() {
+            assert_eq!(&reordered[simulation.chests[pos]].id, id);
+        }
+        let requested = simulation
+            .requested_item
+            .map(|(_, item)| &reordered[item].id);
+        assert_eq!(
+            requested,
+            data.items.requested_item.as_ref().map(|(_, id)| id)
+        );
+    }
+
+    #[test]
+    fn saves_not_fitting_the_catalogue_are_rejected() {
+        let catalogue = catalogue();
+        let mut data = SaveData::parse(&saved_run(&catalogue)).unwrap();
+        let (pos, id) = data.items.chests.iter().next().unwrap();
+        let (pos, id) = (*pos, id.clone());
+
+        let mut shrunk = self::catalogue();
+        shrunk.items.retain(|def| def.id != id);
+        assert!(data.simulation(&shrunk).is_err());
+
+        // A chest missing from the board
+        data.items.chests.remove(&pos);
+        assert!(data.simulation(&catalogue).is_err());
+    }
+
+    #[test]
+    fn saves_of_another_version_are_rejected() {
+        let contents = saved_run(&catalogue());
+        let older = contents.replacen(
+            &format!("version: {SAVE_VERSION}"),
+            &format!("version: {}", SAVE_VERSION - 1),
+            1,
+        );
+        assert!(SaveData::parse(&older).is_err());
+    }
+}
@@ -0,0 +1,841 @@
+use std::collections::VecDeque;
+
+use bevy::{prelude::*, utils::HashMap};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::plugins::locale::Message;
+
+use super::{
+    events::{NewsCategory, NewsFeedUpdate, NewsLevel},
+    resources::Money,
+    status_effects::{Interaction, Stacking, StatusEffect, INTERACTIONS},
+    utils::{
+        self,
+        item::{Item, ItemCatalogue},
+        layout::Layout,
+        SideEffect,
+    },
+};
+
+const DEFAULT_DEBT_LIMIT: i32 = -10000;
+const DEFAULT_MAX_KILLS: i32 = 10;
+pub const DEFAULT_CANCER_TURNS: i32 = 30;
+
+// Each condition can be turned off with e.g. `--debt-limit off`
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LosingConditions {
+    // The run is lost once the balance drops below this
+    pub debt_limit: Option<i32>,
+    pub max_customers_killed: Option<i32>,
+    // Turns between catching cancer and dying from it
+    pub cancer_turns: Option<i32>,
+}
+
+fn condition(flag: &str, default: i32) -> Option<i32> {
+    match utils::cli_arg::<String>(flag).as_deref() {
+        None => Some(default),
+        Some("off") => None,
+        Some(value) => value.parse().map(Some).unwrap_or_else(|_| {
+            bevy::log::warn!("Ignoring invalid value {value:?} for {flag}");
+            Some(default)
+        }),
+    }
+}
+
+// Also what a save without losing conditions falls back to, whatever flags the game was started
+// with this time
+impl Default for LosingConditions {
+    fn default() -> Self {
+        LosingConditions {
+            debt_limit: Some(DEFAULT_DEBT_LIMIT),
+            max_customers_killed: Some(DEFAULT_MAX_KILLS),
+            cancer_turns: Some(DEFAULT_CANCER_TURNS),
+        }
+    }
+}
+
+impl LosingConditions {
+    // For a new run, with the defaults changed by the command line flags
+    pub fn from_args() -> Self {
+        LosingConditions {
+            debt_limit: condition("--debt-limit", DEFAULT_DEBT_LIMIT),
+            max_customers_killed: condition("--max-kills", DEFAULT_MAX_KILLS),
+            cancer_turns: condition("--cancer-turns", DEFAULT_CANCER_TURNS),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum Defeat {
+    Bankrupt,
+    Massacre,
+    Cancer,
+}
+
+impl Defeat {
+    pub fn key(&self) -> &'static str {
+        match self {
+            Defeat::Bankrupt => "defeat.bankrupt",
+            Defeat::Massacre => "defeat.massacre",
+            Defeat::Cancer => "defeat.cancer",
+        }
+    }
+}
+
+// The rules of the game, free of anything that needs a window, a GPU or a sound card.
+// The instruction systems drive it step by step and present the reports it returns.
+#[derive(Clone, Resource, Serialize, Deserialize)]
+pub struct Simulation {
+    // Saved by item id, see `save::SavedItems`
+    #[serde(skip)]
+    pub chests: HashMap<(i32, i32), Item>,
+    pub status_effects: HashMap<StatusEffect, i32>,
+    // Turns left during which an effect cannot be caught again
+    #[serde(default)]
+    pub immunities: HashMap<StatusEffect, i32>,
+    pub money: Money,
+    pub customer_no: i32,
+    #[serde(skip)]
+    pub requested_item: Option<(Message, Item)>,
+    #[serde(skip)]
+    pub prev_requested_item: Option<Item>,
+    pub global_news: VecDeque<Message>,
+    pub war_news: VecDeque<Message>,
+    pub war: bool,
+    pub win: bool,
+    #[serde(default)]
+    pub customers_served: i32,
+    #[serde(default)]
+    pub customers_killed: i32,
+    #[serde(default)]
+    pub losing_conditions: LosingConditions,
+    #[serde(default)]
+    pub defeat: Option<Defeat>,
+}
+
+#[derive(Default)]
+pub struct Report {
+    pub news: Vec<NewsFeedUpdate>,
+    // Cue names from the sound bank
+    pub sounds: Vec<String>,
+    pub moves: Vec<((i32, i32), (i32, i32))>,
+    pub toilet_run: bool,
+    // The chest whose entry in the notes the player forgot
+    pub forgotten_note: Option<(i32, i32)>,
+}
+
+impl Report {
+    pub fn news(&mut self, level: NewsLevel, category: NewsCategory, message: Message) {
+        self.news
+            .push(NewsFeedUpdate::new(level, category, message));
+    }
+
+    pub fn sound(&mut self, cue: &str) {
+        self.sounds.push(cue.to_string());
+    }
+
+    fn append(&mut self, mut other: Report) {
+        self.news.append(&mut other.news);
+        self.sounds.append(&mut other.sounds);
+        self.moves.append(&mut other.moves);
+        self.toilet_run |= other.toilet_run;
+        self.forgotten_note = self.forgotten_note.or(other.forgotten_note);
+    }
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Simulation {
+            chests: default(),
+            status_effects: default(),
+            immunities: default(),
+            money: Money::new(1000),
+            customer_no: 0,
+            requested_item: None,
+            prev_requested_item: None,
+            global_news: default(),
+            war_news: default(),
+            war: false,
+            win: false,
+            customers_served: 0,
+            customers_killed: 0,
+            losing_conditions: default(),
+            defeat: None,
+        }
+    }
+}
+
+impl Simulation {
+    pub fn stock(&mut self, catalogue: &ItemCatalogue, layout: &Layout) {
+        let items = layout.stock(catalogue).into_iter().rev();
+        self.chests = layout.positions().zip(items).collect();
+    }
+
+    pub fn swap_with_first(&mut self, pos: (i32, i32)) {
+        let first = self.chests[&(0, 0)];
+        let other = self.chests[&pos];
+
+        self.chests.insert((0, 0), other);
+        self.chests.insert(pos, first);
+    }
+
+    pub fn presented_item(&self, catalogue: &ItemCatalogue) -> Item {
+        if self.requested_item.is_some() {
+            self.chests[&(0, 0)]
+        } else {
+            catalogue.tutorial_item()
+        }
+    }
+
+    pub fn handle_effects(
+        &mut self,
+        item: Item,
+        catalogue: &ItemCatalogue,
+        rng: &mut impl Rng,
+    ) -> Report {
+        let mut report = Report::default();
+
+        let Some((request, requested)) = self.requested_item.clone() else {
+            let funeral_cost = Money::new(5000);
+            self.money -= funeral_cost;
+            report.sound("gunshot");
+            report.sound("large_hit");
+            report.news(
+                NewsLevel::Wrong,
+                NewsCategory::Story,
+                Message::new("news.tutorial.shot"),
+            );
+            report.news(
+                NewsLevel::Wrong,
+                NewsCategory::Story,
+                Message::new("news.tutorial.alone"),
+            );
+            report.news.push(
+                NewsFeedUpdate::new(
+                    NewsLevel::Wrong,
+                    NewsCategory::Story,
+                    Message::new("news.tutorial.funeral")
+                        .with("cost", funeral_cost)
+                        .then(Message::new("news.balance").with("balance", self.money)),
+                )
+                .with_money(-funeral_cost),
+            );
+            self.apply_status_effect(StatusEffect::Reshuffle, 1);
+
+            return report;
+        };
+
+        let def = &catalogue[item];
+
+        let news = if requested == item {
+            let gain = def.gain(rng);
+            self.money += gain;
+            report.sound("correct");
+
+            let response = Message::new("news.success")
+                .with("found", def.found(rng))
+                .with("gain", gain)
+                .then(Message::new("news.balance").with("balance", self.money));
+            self.customers_served += 1;
+            self.prev_requested_item = Some(item);
+            if def.starts_war() {
+                self.war_news.extend(def.war_news.iter().map(Message::new));
+                self.war = true;
+            }
+            self.requested_item = None;
+            NewsFeedUpdate::new(NewsLevel::Correct, NewsCategory::Sale, response).with_money(gain)
+        } else {
+            let (text, side_effect, sounds) = def.side_effect(rng);
+            report.sounds.extend(sounds);
+
+            let mut money_delta = None;
+            let mut response = Message::new("news.wrong_item")
+                .with("request", request)
+                .with("found", def.found(rng));
+            if let Some(text) = text {
+                response = response.then(text);
+            }
+
+            match side_effect {
+                SideEffect::NoEffect => (),
+                SideEffect::MoneyLoss(sum) => {
+                    self.money -= sum;
+                    money_delta = Some(-sum);
+                    response =
+                        response.then(Message::new("news.balance").with("balance", self.money));
+                }
+                SideEffect::StatusEffectEnable(effect, turns) => {
+                    for message in self.apply_status_effect(effect, turns) {
+                        response = response.then(message);
+                    }
+                }
+                SideEffect::StatusEffectCure(effect) => {
+                    if self.cure_status_effect(effect) {
+                        report.sound("correct");
+                        if let Some(message) = effect.behaviour().on_cure(self) {
+                            response = response.then(message);
+                        }
+                    }
+                }
+                SideEffect::StatusEffectToggle(effect, turns) => {
+                    if self.cure_status_effect(effect) {
+                        report.sound("correct");
+                        if let Some(message) = effect.behaviour().on_cure(self) {
+                            response = response.then(message);
+                        }
+                    } else {
+                        for message in self.apply_status_effect(effect, turns) {
+                            response = response.then(message);
+                        }
+                    }
+                }
+                SideEffect::CustomerKill => {
+                    self.customers_killed += 1;
+                    self.requested_item = None;
+                }
+            }
+            NewsFeedUpdate {
+                money_delta,
+                ..NewsFeedUpdate::new(NewsLevel::Wrong, NewsCategory::Mishap, response)
+            }
+        };
+
+        report.news.push(news);
+        report
+    }
+
+    // How many more turns an active effect lasts, counting the current one, if it runs out at all
+    pub fn remaining_turns(&self, effect: StatusEffect) -> Option<i32> {
+        let turns = self.status_effects.get(&effect)?;
+        effect.behaviour().remaining_turns(self, *turns)
+    }
+
+    pub fn lights_out(&self) -> bool {
+        self.status_effects
+            .keys()
+            .any(|effect| effect.behaviour().lights_out())
+    }
+
+    // How fast animations play, as slowed down by the active effects
+    pub fn animation_speed(&self) -> f32 {
+        self.status_effects
+            .keys()
+            .map(|effect| effect.behaviour().animation_speed(self))
+            .product()
+    }
+
+    pub fn treatment_cost(&self, effect: StatusEffect) -> Option<Money> {
+        if !self.status_effects.contains_key(&effect) {
+            return None;
+        }
+        effect.behaviour().treatment_cost(self)
+    }
+
+    // Can be bought between turns, even on credit
+    pub fn buy_treatment(&mut self, effect: StatusEffect) -> Report {
+        let mut report = Report::default();
+        let Some(cost) = self.treatment_cost(effect) else { return report };
+
+        self.money -= cost;
+        let treated = effect.behaviour().on_treatment(self, &mut report);
+
+        let balance = Message::new("news.balance").with("balance", self.money);
+        let message = match treated {
+            Some(treated) => treated.with("cost", cost).then(balance),
+            None => balance,
+        };
+        report.news.push(
+            NewsFeedUpdate::new(NewsLevel::Event, NewsCategory::Condition, message)
+                .with_money(-cost),
+        );
+        report
+    }
+
+    // Starts an effect, or stacks it onto the running one, following the interaction rules.
+    // Returns the sentences explaining what happened.
+    fn apply_status_effect(&mut self, effect: StatusEffect, turns: i32) -> Vec<Message> {
+        let behaviour = effect.behaviour();
+        let turns = behaviour.turns(self, turns);
+        let name = Message::new(effect.name_key());
+
+        if self.immunities.contains_key(&effect) {
+            return vec![Message::new("news.status.immune").with("effect", name)];
+        }
+
+        let mut messages = Vec::new();
+
+        for interaction in INTERACTIONS {
+            if let Interaction::Cancels {
+                applied,
+                ends,
+                news,
+            } = *interaction
+            {
+                if applied == effect && self.cure_status_effect(ends) {
+                    messages.push(Message::new(news));
+                }
+            }
+        }
+
+        match self.status_effects.get(&effect) {
+            Some(&remaining) => {
+                let (turns, key) = match behaviour.stacking() {
+                    Stacking::Refresh => (remaining.max(turns), "news.status.refreshed"),
+                    Stacking::Stack => (remaining + turns, "news.status.stacked"),
+                };
+                self.status_effects.insert(effect, turns);
+                messages.push(
+                    Message::new(key)
+                        .with("effect", name)
+                        .with("turns", turns + 1),
+                );
+            }
+            None => {
+                self.status_effects.insert(effect, turns);
+                messages.extend(behaviour.on_apply(self));
+            }
+        }
+
+        for interaction in INTERACTIONS {
+            if let Interaction::Amplifies {
+                amplifier,
+                target,
+                news,
+            } = *interaction
+            {
+                let pair = [amplifier, target];
+                if pair.contains(&effect)
+                    && pair
+                        .iter()
+                        .all(|effect| self.status_effects.contains_key(effect))
+                {
+                    messages.push(Message::new(news));
+                }
+            }
+        }
+
+        messages
+    }
+
+    // Ends an effect early, without its expiry
+    pub fn cure_status_effect(&mut self, effect: StatusEffect) -> bool {
+        let cured = self.status_effects.remove(&effect).is_some();
+        if cured {
+            self.start_immunity(effect);
+        }
+        cured
+    }
+
+    fn start_immunity(&mut self, effect: StatusEffect) {
+        let turns = effect.behaviour().immunity();
+        if turns > 0 {
+            self.immunities.insert(effect, turns);
+        }
+    }
+
+    pub fn is_amplified(&self, effect: StatusEffect) -> bool {
+        INTERACTIONS.iter().any(|interaction| {
+            matches!(*interaction, Interaction::Amplifies { amplifier, target, .. }
+                if target == effect && self.status_effects.contains_key(&amplifier))
+        })
+    }
+
+    pub fn tick_status_effects(&mut self, rng: &mut impl Rng) -> Report {
+        let mut report = Report::default();
+
+        self.immunities.retain(|_, turns| {
+            *turns -= 1;
+            *turns > 0
+        });
+
+        let amplified: Vec<_> = StatusEffect::ALL
+            .iter()
+            .copied()
+            .filter(|&effect| self.is_amplified(effect))
+            .collect();
+
+        let mut active = Vec::new();
+        for &effect in StatusEffect::ALL {
+            let behaviour = effect.behaviour();
+            let Some(turns) = self.status_effects.get_mut(&effect) else { continue };
+
+            if !amplified.contains(&effect) {
+                *turns -= 1;
+            }
+            if *turns < 0 {
+                self.status_effects.remove(&effect);
+                self.start_immunity(effect);
+                behaviour.on_expire(self, &mut report);
+            } else {
+                active.push(behaviour);
+            }
+        }
+
+        for behaviour in active {
+            behaviour.on_tick(self, rng, &mut report);
+        }
+
+        report
+    }
+
+    fn check_defeat(&self) -> Option<Defeat> {
+        let conditions = &self.losing_conditions;
+
+        if self.defeat.is_some() {
+            self.defeat
+        } else if matches!(conditions.debt_limit, Some(limit) if self.money.value() < limit) {
+            Some(Defeat::Bankrupt)
+        } else if matches!(conditions.max_customers_killed, Some(max) if self.customers_killed >= max)
+        {
+            Some(Defeat::Massacre)
+        } else {
+            None
+        }
+    }
+
+    pub fn end_of_turn(&mut self, catalogue: &ItemCatalogue, rng: &mut impl Rng) -> Report {
+        let mut report = Report::default();
+
+        self.defeat = self.check_defeat();
+        if let Some(defeat) = self.defeat {
+            report.sound("sad_trombone");
+            report.news(
+                NewsLevel::Wrong,
+                NewsCategory::Story,
+                Message::new(defeat.key()),
+            );
+            return report;
+        }
+
+        if let Some(gnews) = self.global_news.pop_front() {
+            report.news(NewsLevel::External, NewsCategory::Headline, gnews);
+        }
+        if let Some(gnews) = self.war_news.pop_front() {
+            if self.war_news.len() == 3 {
+                report.sound("gunshot");
+            }
+            if !self.war_news.is_empty() {
+                report.sound("nuke_siren");
+            }
+            report.news(NewsLevel::External, NewsCategory::War, gnews);
+        }
+
+        if !self.win && self.war && self.war_news.is_empty() {
+            self.win = true;
+            report.sound("win_music");
+        }
+
+        if let Some((request, _)) = &self.requested_item {
+            report.news(
+                NewsLevel::Event,
+                NewsCategory::Customer,
+                Message::new("news.still_waiting").with("request", request.clone()),
+            );
+        } else {
+            if self.customer_no != 21 || self.war {
+                // Customer 21 advances only on success
+                self.customer_no += 1;
+            }
+
+            let new_item = catalogue.new_random(self.customer_no, self.prev_requested_item, rng);
+
+            if let Some(gnews) = self
+                .prev_requested_item
+                .and_then(|item| catalogue[item].global_side_effect(rng))
+            {
+                self.global_news.push_back(gnews);
+            }
+
+            self.prev_requested_item = None;
+            let request = catalogue[new_item].request(rng);
+
+            if self.customer_no == 6 {
+                report.news(
+                    NewsLevel::Event,
+                    NewsCategory::Story,
+                    Message::new("news.advertise"),
+                );
+            }
+
+            let arrival = if self.customer_no == 21 {
+                "news.shady_customer"
+            } else {
+                "news.new_customer"
+            };
+            report.news(
+                NewsLevel::Event,
+                NewsCategory::Customer,
+                Message::new(arrival)
+                    .with("turn", self.customer_no)
+                    .with("request", request.clone()),
+            );
+
+            self.requested_item = Some((request, new_item));
+        }
+
+        report
+    }
+
+    // A whole turn without any presentation in between, for headless runs
+    pub fn play_turn(
+        &mut self,
+        pos: (i32, i32),
+        catalogue: &ItemCatalogue,
+        rng: &mut impl Rng,
+    ) -> Report {
+        self.swap_with_first(pos);
+        let item = self.presented_item(catalogue);
+
+        let mut report = self.handle_effects(item, catalogue, rng);
+        report.append(self.tick_status_effects(rng));
+        report.append(self.end_of_turn(catalogue, rng));
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::states::play::{
+        balance::pick_chest, resources::GameRng, utils::layout::LayoutPresets,
+    };
+
+    const TURNS: i32 = 2000;
+    const SEEDS: [u64; 4] = [1, 7, 42, 1234];
+
+    fn catalogue() -> ItemCatalogue {
+        ItemCatalogue::from_ron(include_bytes!("../../../assets/items.catalogue.ron")).unwrap()
+    }
+
+    fn layout() -> Layout {
+        let presets =
+            LayoutPresets::from_ron(include_bytes!("../../../assets/boards.layouts.ron")).unwrap();
+        presets.boards[&presets.default].clone()
+    }
+
+    fn new_run(
+        catalogue: &ItemCatalogue,
+        layout: &Layout,
+        conditions: LosingConditions,
+    ) -> Simulation {
+        let mut simulation = Simulation {
+            losing_conditions: conditions,
+            ..default()
+        };
+        simulation.stock(catalogue, layout);
+        simulation
+    }
+
+    fn endless() -> LosingConditions {
+        LosingConditions {
+            debt_limit: None,
+            max_customers_killed: None,
+            cancer_turns: None,
+        }
+    }
+
+    fn transcript(report: &Report) -> String {
+        ron::to_string(&(
+            &report.news,
+            &report.sounds,
+            &report.moves,
+            report.toilet_run,
+            report.forgotten_note,
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn every_chest_stays_filled() {
+        let catalogue = catalogue();
+        let layout = layout();
+        let positions: HashSet<(i32, i32)> = layout.positions().collect();
+
+        for seed in SEEDS {
+            let mut rng = GameRng::new(seed).rng;
+            let mut simulation = new_run(&catalogue, &layout, endless());
+
+            for turn in 0..TURNS {
+                let pos = pick_chest(&simulation, &mut rng);
+                simulation.play_turn(pos, &catalogue, &mut rng);
+
+                let filled: HashSet<(i32, i32)> = simulation.chests.keys().copied().collect();
+                assert_eq!(filled, positions, "seed {seed}, turn {turn}");
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_plays_the_same_run() {
+        let catalogue = catalogue();
+        let layout = layout();
+
+        for seed in SEEDS {
+            let mut first_rng = GameRng::new(seed).rng;
+            let mut second_rng = GameRng::new(seed).rng;
+            let mut first = new_run(&catalogue, &layout, endless());
+            let mut second = new_run(&catalogue, &layout, endless());
+
+            for turn in 0..TURNS {
+                let first_report = first.play_turn(
+                    pick_chest(&first, &mut first_rng),
+                    &catalogue,
+                    &mut first_rng,
+                );
+                let second_report = second.play_turn(
+                    pick_chest(&second, &mut second_rng),
+                    &catalogue,
+                    &mut second_rng,
+                );
+
+                assert_eq!(
+                    transcript(&first_report),
+                    transcript(&second_report),
+                    "seed {seed}, turn {turn}"
+                );
+            }
+            assert_eq!(first.money.value(), second.money.value());
+            assert_eq!(first.chests, second.chests);
+        }
+    }
+
+    #[test]
+    fn money_only_changes_through_reported_deltas() {
+        let catalogue = catalogue();
+        let layout = layout();
+
+        for seed in SEEDS {
+            let mut rng = GameRng::new(seed).rng;
+            let mut simulation = new_run(&catalogue, &layout, endless());
+
+            for turn in 0..TURNS {
+                let before = simulation.money.value();
+                let pos = pick_chest(&simulation, &mut rng);
+                let report = simulation.play_turn(pos, &catalogue, &mut rng);
+
+                let reported: i32 = report
+                    .news
+                    .iter()
+                    .filter_map(|news| news.money_delta)
+                    .map(|delta| delta.value())
+                    .sum();
+                assert_eq!(
+                    simulation.money.value() - before,
+                    reported,
+                    "seed {seed}, turn {turn}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn runs_end_exactly_at_the_losing_conditions() {
+        let catalogue = catalogue();
+        let layout = layout();
+        let (debt_limit, max_killed) = (-10000, 10);
+        let conditions = LosingConditions {
+            debt_limit: Some(debt_limit),
+            max_customers_killed: Some(max_killed),
+            cancer_turns: Some(30),
+        };
+
+        for seed in SEEDS {
+            let mut rng = GameRng::new(seed).rng;
+            let mut simulation = new_run(&catalogue, &layout, conditions.clone());
+
+            for turn in 0..TURNS {
+                let pos = pick_chest(&simulation, &mut rng);
+                simulation.play_turn(pos, &catalogue, &mut rng);
+
+                let broken = simulation.money.value() < debt_limit
+                    || simulation.customers_killed >= max_killed;
+                match simulation.defeat {
+                    Some(Defeat::Bankrupt) => assert!(simulation.money.value() < debt_limit),
+                    Some(Defeat::Massacre) => assert!(simulation.customers_killed >= max_killed),
+                    Some(Defeat::Cancer) => {}
+                    None => assert!(!broken, "seed {seed}, turn {turn}"),
+                }
+                if simulation.defeat.is_some() {
+                    break;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn check_defeat_fires_at_the_limits() {
+        let mut simulation = Simulation {
+            losing_conditions: LosingConditions {
+                debt_limit: Some(-500),
+                max_customers_killed: Some(3),
+                cancer_turns: None,
+            },
+            ..default()
+        };
+
+        simulation.money = Money::new(-500);
+        assert!(simulation.check_defeat().is_none());
+        simulation.money = Money::new(-501);
+        assert!(matches!(simulation.check_defeat(), Some(Defeat::Bankrupt)));
+
+        simulation.money = Money::new(0);
+        simulation.customers_killed = 2;
+        assert!(simulation.check_defeat().is_none());
+        simulation.customers_killed = 3;
+        assert!(matches!(simulation.check_defeat(), Some(Defeat::Massacre)));
+
+        // Turned off conditions never fire
+        simulation.losing_conditions = endless();
+        simulation.money = Money::new(i32::MIN / 2);
+        simulation.customers_killed = i32::MAX;
+        assert!(simulation.check_defeat().is_none());
+    }
+
+    #[test]
+    fn cancer_kills_once_its_turns_run_out() {
+        let turns = 8;
+        let mut rng = GameRng::new(3).rng;
+        let mut simulation = Simulation {
+            losing_conditions: LosingConditions {
+                cancer_turns: Some(turns),
+                ..endless()
+            },
+            ..default()
+        };
+        simulation.apply_status_effect(StatusEffect::Cancer, turns);
+
+        // The turn it was caught on counts as well
+        for _ in 0..turns {
+            simulation.tick_status_effects(&mut rng);
+            assert!(simulation.defeat.is_none());
+        }
+        simulation.tick_status_effects(&mut rng);
+        assert!(matches!(simulation.defeat, Some(Defeat::Cancer)));
+    }
+
+    #[test]
+    fn treatment_pushes_cancer_back_a_stage() {
+        let mut simulation = Simulation {
+            losing_conditions: LosingConditions {
+                cancer_turns: Some(8),
+                ..endless()
+            },
+            money: Money::new(0),
+            ..default()
+        };
+        // Stages of two turns, and three turns elapsed
+        simulation.status_effects.insert(StatusEffect::Cancer, 5);
+
+        simulation.buy_treatment(StatusEffect::Cancer);
+        assert_eq!(simulation.status_effects[&StatusEffect::Cancer], 7);
+        assert_eq!(simulation.money.value(), -4000);
+
+        simulation.buy_treatment(StatusEffect::Cancer);
+        assert!(simulation.remaining_turns(StatusEffect::Cancer).is_none());
+        assert_eq!(simulation.money.value(), -6000);
+        assert!(simulation.treatment_cost(StatusEffect::Cancer).is_none());
+    }
+}
@@ -0,0 +1,162 @@
+use rand::{seq::SliceRandom, Rng, RngCore};
+
+use crate::{
+    plugins::{locale::Message, post_process::PostEffect},
+    states::play::{
+        events::{NewsCategory, NewsFeedUpdate, NewsLevel},
+        resources::Money,
+        simulation::{Defeat, Report, Simulation, DEFAULT_CANCER_TURNS},
+    },
+};
+
+use super::{StatusEffect, StatusEffectBehaviour};
+
+const STAGES: i32 = 4;
+// Per stage past the first
+const MEDICAL_BILL: i32 = 100;
+// Per stage
+const TREATMENT_COST: i32 = 2000;
+
+// Cancer takes as many turns as it needs to kill, split into equal stages. Without that losing
+// condition it still progresses at the default pace, but never kills.
+fn stage_turns(simulation: &Simulation) -> i32 {
+    let turns = simulation
+        .losing_conditions
+        .cancer_turns
+        .unwrap_or(DEFAULT_CANCER_TURNS);
+    (turns / STAGES).max(1)
+}
+
+// Turns between catching it and dying from it, endless without that losing condition
+fn total_turns(simulation: &Simulation) -> i32 {
+    simulation
+        .losing_conditions
+        .cancer_turns
+        .unwrap_or(i32::MAX)
+}
+
+fn stage_after(simulation: &Simulation, remaining: i32) -> i32 {
+    let elapsed = total_turns(simulation).saturating_sub(remaining).max(0);
+    (elapsed / stage_turns(simulation) + 1).min(STAGES)
+}
+
+fn stage(simulation: &Simulation) -> Option<i32> {
+    let remaining = simulation.status_effects.get(&StatusEffect::Cancer)?;
+    Some(stage_after(simulation, *remaining))
+}
+
+// Grows through its stages, each one worse than the last, and kills the player once it runs out
+// unless that losing condition is turned off
+pub struct Cancer;
+
+impl StatusEffectBehaviour for Cancer {
+    fn icon(&self) -> &'static str {
+        "☢"
+    }
+
+    fn name_key(&self) -> &'static str {
+        "status.cancer.name"
+    }
+
+    fn description_key(&self) -> &'static str {
+        "status.cancer.description"
+    }
+
+    // As long as the losing condition allows, whatever caused it
+    fn turns(&self, simulation: &Simulation, _turns: i32) -> i32 {
+        total_turns(simulation)
+    }
+
+    // Without a time limit it never runs out
+    fn remaining_turns(&self, simulation: &Simulation, turns: i32) -> Option<i32> {
+        simulation.losing_conditions.cancer_turns.map(|_| turns + 1)
+    }
+
+    fn details(&self, simulation: &Simulation) -> Option<Message> {
+        stage(simulation).map(|stage| Message::new("status.cancer.stage").with("stage", stage))
+    }
+
+    fn post_effect(&self, simulation: &Simulation) -> Option<(PostEffect, f32)> {
+        let strength = stage(simulation).map_or(0.0, |stage| stage as f32 / STAGES as f32);
+        Some((PostEffect::Radiation, strength))
+    }
+
+    // Sluggish from the third stage on
+    fn animation_speed(&self, simulation: &Simulation) -> f32 {
+        match stage(simulation) {
+            Some(stage) if stage >= 3 => 1.0 - 0.25 * (stage - 2) as f32,
+            _ => 1.0,
+        }
+    }
+
+    fn treatment_cost(&self, simulation: &Simulation) -> Option<Money> {
+        stage(simulation).map(|stage| Money::new(TREATMENT_COST * stage))
+    }
+
+    // Pushes the cancer back by a stage, or cures it from the first one
+    fn on_treatment(&self, simulation: &mut Simulation, report: &mut Report) -> Option<Message> {
+        let stage = stage(simulation)?;
+
+        if stage == 1 {
+            simulation.cure_status_effect(StatusEffect::Cancer);
+            report.sound("correct");
+            return Some(Message::new("news.cancer.treatment_cured"));
+        }
+
+        let total = total_turns(simulation);
+        let stage_turns = stage_turns(simulation);
+        if let Some(remaining) = simulation.status_effects.get_mut(&StatusEffect::Cancer) {
+            *remaining = remaining.saturating_add(stage_turns).min(total);
+        }
+        Some(Message::new("news.cancer.treatment").with("stage", stage - 1))
+    }
+
+    fn on_apply(&self, _simulation: &mut Simulation) -> Option<Message> {
+        Some(Message::new("news.cancer_caught"))
+    }
+
+    fn on_cure(&self, _simulation: &mut Simulation) -> Option<Message> {
+        Some(Message::new("news.cancer_cured"))
+    }
+
+    fn on_tick(&self, simulation: &mut Simulation, rng: &mut dyn RngCore, report: &mut Report) {
+        let Some(&remaining) = simulation.status_effects.get(&StatusEffect::Cancer) else { return };
+        let stage = stage_after(simulation, remaining);
+
+        if stage > stage_after(simulation, remaining.saturating_add(1)) {
+            report.sound("sad_trombone");
+            report.news(
+                NewsLevel::Wrong,
+                NewsCategory::Condition,
+                Message::new(format!("news.cancer.stage.{stage}")),
+            );
+        }
+
+        // Medical bills from the second stage on
+        if stage >= 2 {
+            let bill = Money::new(MEDICAL_BILL * (stage - 1));
+            simulation.money -= bill;
+            report.news.push(
+                NewsFeedUpdate::new(
+                    NewsLevel::Wrong,
+                    NewsCategory::Condition,
+                    Message::new("news.cancer.bill").with("money", bill),
+                )
+                .with_money(-bill),
+            );
+        }
+
+        // Memory loss in the last stage, sometimes already in the one before
+        if stage == 4 || (stage == 3 && rng.gen_bool(1.0 / 3.0)) {
+            let mut positions: Vec<(i32, i32)> = simulation.chests.keys().copied().collect();
+            positions.sort();
+            report.forgotten_note = positions.choose(rng).copied();
+        }
+    }
+
+    fn on_expire(&self, simulation: &mut Simulation, _report: &mut Report) {
+        if simulation.losing_conditions.cancer_turns.is_some() {
+            simulation.defeat = Some(Defeat::Cancer);
+        }
+    }
+}
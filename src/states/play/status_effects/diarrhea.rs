@@ -0,0 +1,82 @@
+use rand::{Rng, RngCore};
+
+use crate::{
+    plugins::{locale::Message, post_process::PostEffect},
+    states::play::{
+        events::{NewsCategory, NewsLevel},
+        simulation::{Report, Simulation},
+    },
+};
+
+use super::{Stacking, StatusEffectBehaviour};
+
+// A run to the toilet every turn, which the customer may not wait for
+pub struct Diarrhea;
+
+impl StatusEffectBehaviour for Diarrhea {
+    fn icon(&self) -> &'static str {
+        "🚽"
+    }
+
+    fn name_key(&self) -> &'static str {
+        "status.diarrhea.name"
+    }
+
+    fn description_key(&self) -> &'static str {
+        "status.diarrhea.description"
+    }
+
+    fn stacking(&self) -> Stacking {
+        Stacking::Stack
+    }
+
+    // The stomach needs a break after all that
+    fn immunity(&self) -> i32 {
+        2
+    }
+
+    fn post_effect(&self, _simulation: &Simulation) -> Option<(PostEffect, f32)> {
+        Some((PostEffect::Nausea, 0.6))
+    }
+
+    fn on_cure(&self, _simulation: &mut Simulation) -> Option<Message> {
+        Some(Message::new("news.diarrhea_cured"))
+    }
+
+    fn on_tick(&self, simulation: &mut Simulation, mut rng: &mut dyn RngCore, report: &mut Report) {
+        report.sound("flush");
+
+        if simulation.requested_item.is_some() {
+            if rng.gen_range(0..=10) > 7 {
+                simulation.requested_item = None;
+                report.news(
+                    NewsLevel::Event,
+                    NewsCategory::Condition,
+                    Message::pick("news.toilet.customer_leaves", &mut rng),
+                );
+            } else {
+                report.news(
+                    NewsLevel::Event,
+                    NewsCategory::Condition,
+                    Message::pick("news.toilet.customer_waits", &mut rng),
+                );
+            }
+        } else {
+            report.news(
+                NewsLevel::Event,
+                NewsCategory::Condition,
+                Message::new("news.toilet.no_customer"),
+            );
+        }
+
+        report.toilet_run = true;
+    }
+
+    fn on_expire(&self, _simulation: &mut Simulation, report: &mut Report) {
+        report.news(
+            NewsLevel::Event,
+            NewsCategory::Condition,
+            Message::new("news.stomach_better"),
+        );
+    }
+}
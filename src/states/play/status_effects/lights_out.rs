@@ -0,0 +1,42 @@
+use crate::{
+    plugins::{locale::Message, post_process::PostEffect},
+    states::play::{
+        events::{NewsCategory, NewsLevel},
+        simulation::{Report, Simulation},
+    },
+};
+
+use super::StatusEffectBehaviour;
+
+// Only darkens the junkyard
+pub struct LightsOut;
+
+impl StatusEffectBehaviour for LightsOut {
+    fn icon(&self) -> &'static str {
+        "💡"
+    }
+
+    fn name_key(&self) -> &'static str {
+        "status.lights_out.name"
+    }
+
+    fn description_key(&self) -> &'static str {
+        "status.lights_out.description"
+    }
+
+    fn post_effect(&self, _simulation: &Simulation) -> Option<(PostEffect, f32)> {
+        Some((PostEffect::Blackout, 1.0))
+    }
+
+    fn lights_out(&self) -> bool {
+        true
+    }
+
+    fn on_expire(&self, _simulation: &mut Simulation, report: &mut Report) {
+        report.news(
+            NewsLevel::Event,
+            NewsCategory::Condition,
+            Message::new("news.lights_back"),
+        );
+    }
+}
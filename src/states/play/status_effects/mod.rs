@@ -0,0 +1,173 @@
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::plugins::{locale::Message, post_process::PostEffect};
+
+use super::{
+    resources::Money,
+    simulation::{Report, Simulation},
+};
+
+mod cancer;
+mod diarrhea;
+mod lights_out;
+mod reshuffle;
+mod trippy;
+
+// Declares the status effects along with the type implementing each
+macro_rules! status_effects {
+    ($($effect:ident => $behaviour:expr),* $(,)?) => {
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+        pub enum StatusEffect {
+            $($effect),*
+        }
+
+        impl StatusEffect {
+            pub const ALL: &'static [StatusEffect] = &[$(StatusEffect::$effect),*];
+
+            pub fn behaviour(&self) -> &'static dyn StatusEffectBehaviour {
+                match self {
+                    $(StatusEffect::$effect => &$behaviour),*
+                }
+            }
+        }
+    };
+}
+
+// The registry of status effects, in the order they take their turn, which the seeded game
+// depends on. A new effect takes a module with its behaviour and a line here.
+status_effects! {
+    LightsOut => lights_out::LightsOut,
+    Trippy => trippy::Trippy,
+    Reshuffle => reshuffle::Reshuffle,
+    Diarrhea => diarrhea::Diarrhea,
+    Cancer => cancer::Cancer,
+}
+
+impl StatusEffect {
+    pub fn icon(&self) -> &'static str {
+        self.behaviour().icon()
+    }
+
+    pub fn name_key(&self) -> &'static str {
+        self.behaviour().name_key()
+    }
+
+    pub fn description_key(&self) -> &'static str {
+        self.behaviour().description_key()
+    }
+}
+
+// What happens when an effect is applied again while still active
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Stacking {
+    // Starts over with the longer of the two durations
+    Refresh,
+    // Adds the new turns to the remaining ones
+    Stack,
+}
+
+// How two status effects treat each other. Each rule is explained in the news when it kicks in.
+pub enum Interaction {
+    // Applying the first effect ends the second
+    Cancels {
+        applied: StatusEffect,
+        ends: StatusEffect,
+        news: &'static str,
+    },
+    // The target does not count down while the amplifier is active
+    Amplifies {
+        amplifier: StatusEffect,
+        target: StatusEffect,
+        news: &'static str,
+    },
+}
+
+pub const INTERACTIONS: &[Interaction] = &[
+    Interaction::Cancels {
+        applied: StatusEffect::Diarrhea,
+        ends: StatusEffect::Trippy,
+        news: "news.interaction.sobered",
+    },
+    Interaction::Amplifies {
+        amplifier: StatusEffect::LightsOut,
+        target: StatusEffect::Trippy,
+        news: "news.interaction.dark_trip",
+    },
+];
+
+// What a status effect does to the simulation over its lifetime, and how it shows. Every hook
+// but the ones naming the effect has a default, so an effect only implements the ones it needs.
+pub trait StatusEffectBehaviour: Send + Sync {
+    // A glyph from egui's built-in emoji font
+    fn icon(&self) -> &'static str;
+
+    // String table keys
+    fn name_key(&self) -> &'static str;
+    fn description_key(&self) -> &'static str;
+
+    fn stacking(&self) -> Stacking {
+        Stacking::Refresh
+    }
+
+    // Turns after the effect ends or is cured during which it cannot be caught again
+    fn immunity(&self) -> i32 {
+        0
+    }
+
+    // How long the effect lasts when caught for `turns` turns
+    fn turns(&self, _simulation: &Simulation, turns: i32) -> i32 {
+        turns
+    }
+
+    // How many more turns the effect lasts with `turns` left, counting the current one, or
+    // nothing if it never runs out
+    fn remaining_turns(&self, _simulation: &Simulation, turns: i32) -> Option<i32> {
+        Some(turns + 1)
+    }
+
+    // An extra line for the side panel
+    fn details(&self, _simulation: &Simulation) -> Option<Message> {
+        None
+    }
+
+    // The screen effect showing the status effect, and how strongly it shows
+    fn post_effect(&self, _simulation: &Simulation) -> Option<(PostEffect, f32)> {
+        None
+    }
+
+    fn lights_out(&self) -> bool {
+        false
+    }
+
+    // How fast animations play while the effect is active
+    fn animation_speed(&self, _simulation: &Simulation) -> f32 {
+        1.0
+    }
+
+    // What treating the effect from the side panel costs, if it can be treated
+    fn treatment_cost(&self, _simulation: &Simulation) -> Option<Money> {
+        None
+    }
+
+    // Runs once a treatment is paid for. The returned sentence reports it.
+    fn on_treatment(&self, _simulation: &mut Simulation, _report: &mut Report) -> Option<Message> {
+        None
+    }
+
+    // Runs when the effect starts. The returned sentence follows the news of whatever caused it.
+    fn on_apply(&self, _simulation: &mut Simulation) -> Option<Message> {
+        None
+    }
+
+    // Runs when an item cures the effect. The returned sentence follows the news of the item.
+    fn on_cure(&self, _simulation: &mut Simulation) -> Option<Message> {
+        None
+    }
+
+    // Runs at the end of every turn the effect is still active for
+    fn on_tick(&self, _simulation: &mut Simulation, _rng: &mut dyn RngCore, _report: &mut Report) {}
+
+    // Runs once the effect has run out, but not when it is cured
+    fn on_expire(&self, _simulation: &mut Simulation, _report: &mut Report) {}
+}
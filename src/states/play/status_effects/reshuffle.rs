@@ -0,0 +1,37 @@
+use bevy::utils::HashMap;
+use rand::{seq::SliceRandom, RngCore};
+
+use crate::states::play::simulation::{Report, Simulation};
+
+use super::StatusEffectBehaviour;
+
+// Moves every item to another chest, once per turn
+pub struct Reshuffle;
+
+impl StatusEffectBehaviour for Reshuffle {
+    fn icon(&self) -> &'static str {
+        "🔀"
+    }
+
+    fn name_key(&self) -> &'static str {
+        "status.reshuffle.name"
+    }
+
+    fn description_key(&self) -> &'static str {
+        "status.reshuffle.description"
+    }
+
+    fn on_tick(&self, simulation: &mut Simulation, rng: &mut dyn RngCore, report: &mut Report) {
+        let mut positions: Vec<(i32, i32)> = simulation.chests.keys().copied().collect();
+        positions.sort();
+        let mut positions2 = positions.clone();
+        positions2.shuffle(rng);
+
+        let mut new_chests = HashMap::new();
+        for (from, to) in positions.into_iter().zip(positions2) {
+            new_chests.insert(to, simulation.chests[&from]);
+            report.moves.push((from, to));
+        }
+        simulation.chests = new_chests;
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{
+    plugins::{locale::Message, post_process::PostEffect},
+    states::play::{
+        events::{NewsCategory, NewsLevel},
+        simulation::{Report, Simulation},
+    },
+};
+
+use super::{Stacking, StatusEffectBehaviour};
+
+// Only distorts the picture
+pub struct Trippy;
+
+impl StatusEffectBehaviour for Trippy {
+    fn icon(&self) -> &'static str {
+        "🌀"
+    }
+
+    fn name_key(&self) -> &'static str {
+        "status.trippy.name"
+    }
+
+    fn description_key(&self) -> &'static str {
+        "status.trippy.description"
+    }
+
+    fn stacking(&self) -> Stacking {
+        Stacking::Stack
+    }
+
+    fn post_effect(&self, _simulation: &Simulation) -> Option<(PostEffect, f32)> {
+        Some((PostEffect::Trippy, 1.0))
+    }
+
+    fn on_expire(&self, _simulation: &mut Simulation, report: &mut Report) {
+        report.news(
+            NewsLevel::Event,
+            NewsCategory::Condition,
+            Message::new("news.vision_back"),
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+use bevy_egui::{
+    egui::{self, Align, Color32, Label, Layout, RichText, Sense},
+    EguiContexts,
+};
+
+use crate::{
+    plugins::{
+        locale::{Arg, Message, Texts},
+        settings::Settings,
+    },
+    states::{
+        play::{
+            export,
+            resources::{self, ExportOutcome, GameRng, NewsFeed, RunSummary},
+            simulation::Simulation,
+        },
+        GameState,
+    },
+};
+
+pub fn add_game_over_systems(app: &mut App) {
+    app.add_system(
+        summarize_run
+            .before(resources::reset_resources)
+            .in_schedule(OnExit(GameState::Play)),
+    )
+    .add_system(resources::clear_export_outcome.in_schedule(OnEnter(GameState::GameOver)))
+    .add_system(end_screen.in_set(OnUpdate(GameState::GameOver)))
+    .add_system(clear_summary.in_schedule(OnExit(GameState::GameOver)));
+}
+
+// Taken on the way out of a lost run, before the play resources are reset
+fn summarize_run(
+    mut commands: Commands,
+    simulation: Res<Simulation>,
+    rng: Res<GameRng>,
+    news_feed: Res<NewsFeed>,
+) {
+    let Some(defeat) = simulation.defeat else { return };
+
+    commands.insert_resource(RunSummary {
+        defeat,
+        seed: rng.seed,
+        customer_no: simulation.customer_no,
+        customers_served: simulation.customers_served,
+        customers_killed: simulation.customers_killed,
+        money: simulation.money,
+        news_feed: news_feed.clone(),
+    });
+}
+
+fn end_screen(
+    mut contexts: EguiContexts,
+    mut next_state: ResMut<NextState<GameState>>,
+    summary: Res<RunSummary>,
+    texts: Texts,
+    settings: Res<Settings>,
+    mut export_outcome: ResMut<ExportOutcome>,
+) {
+    let ctx = contexts.ctx_mut();
+
+    egui::Window::new("game_over")
+        .title_bar(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.with_layout(Layout::top_down(Align::Center), |ui| {
+                ui.heading(
+                    RichText::new(texts.get("game_over.title"))
+                        .size(63.0)
+                        .color(Color32::RED),
+                );
+                ui.label(texts.get(summary.defeat.key()));
+                ui.add_space(20.0);
+
+                for (key, arg) in [
+                    ("game_over.customer_no", Arg::from(summary.customer_no)),
+                    ("game_over.served", Arg::from(summary.customers_served)),
+                    ("game_over.killed", Arg::from(summary.customers_killed)),
+                    ("game_over.balance", Arg::from(summary.money)),
+                ] {
+                    ui.label(texts.message(&Message::new(key).with("value", arg)));
+                }
+                ui.label(
+                    RichText::new(texts.message(
+                        &Message::new("hud.seed").with("seed", Arg::Text(summary.seed.to_string())),
+                    ))
+                    .color(Color32::GRAY),
+                );
+                ui.add_space(20.0);
+
+                if ui
+                    .add(
+                        Label::new(RichText::new(texts.get("menu.restart")).size(42.0))
+                            .sense(Sense::click()),
+                    )
+                    .clicked()
+                {
+                    next_state.set(GameState::LoadPlay);
+                }
+                if ui
+                    .add(
+                        Label::new(RichText::new(texts.get("menu.main_menu")).size(42.0))
+                            .sense(Sense::click()),
+                    )
+                    .clicked()
+                {
+                    next_state.set(GameState::MainMenu);
+                }
+                if ui
+                    .add(
+                        Label::new(RichText::new(texts.get("menu.export")).size(42.0))
+                            .sense(Sense::click()),
+                    )
+                    .clicked()
+                {
+                    let result = export::export(
+                        &summary.news_feed,
+                        summary.seed,
+                        Some(&summary),
+                        settings.export_format,
+                        &texts,
+                    );
+                    export_outcome.0 = Some(export::outcome(&result));
+                }
+
+                if let Some(outcome) = export_outcome.0.as_ref() {
+                    ui.add_space(20.0);
+                    ui.label(texts.message(outcome));
+                }
+            });
+        });
+}
+
+fn clear_summary(mut commands: Commands) {
+    commands.remove_resource::<RunSummary>();
+}
@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+
+use crate::{
+    plugins::input::{Action, Actions},
+    states::play::{
+        components::PointerLight,
+        resources::{Chests, HoveredChest, Instructions},
+        simulation::Simulation,
+        utils::layout::Layout,
+        Unpaused,
+    },
+};
+
+use super::{hover, select_hovered};
+
+// How far the analog stick has to be pushed, and how often a held stick repeats
+const STICK_THRESHOLD: f32 = 0.5;
+const STICK_REPEAT: f32 = 0.25;
+
+pub fn add_grid_cursor_systems(app: &mut App) {
+    app.add_system(move_cursor.in_set(Unpaused))
+        .add_system(confirm.in_set(Unpaused));
+}
+
+fn pressed_direction(actions: &mut Actions) -> IVec2 {
+    let mut direction = IVec2::ZERO;
+
+    for (action, step) in [
+        (Action::Left, IVec2::NEG_X),
+        (Action::Right, IVec2::X),
+        (Action::Up, IVec2::Y),
+        (Action::Down, IVec2::NEG_Y),
+    ] {
+        if actions.just_pressed(action) {
+            direction += step;
+        }
+    }
+
+    direction
+}
+
+fn stick_direction(gamepads: &Gamepads, axes: &Axis<GamepadAxis>) -> Vec2 {
+    gamepads
+        .iter()
+        .map(|gamepad| {
+            Vec2::new(
+                axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))
+                    .unwrap_or(0.0),
+                axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY))
+                    .unwrap_or(0.0),
+            )
+        })
+        .find(|stick| stick.length() > STICK_THRESHOLD)
+        .unwrap_or(Vec2::ZERO)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn move_cursor(
+    mut commands: Commands,
+    mut actions: Actions,
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    time: Res<Time>,
+    mut stick_cooldown: Local<f32>,
+    mut hovered_chest: ResMut<HoveredChest>,
+    chests: Res<Chests>,
+    objects: Query<&mut Transform, Without<PointerLight>>,
+    instructions: Res<Instructions>,
+    mut pointer_light: Query<&mut Transform, With<PointerLight>>,
+    layout: Res<Layout>,
+    simulation: Res<Simulation>,
+) {
+    if !instructions.0.is_empty() {
+        return;
+    }
+
+    let mut direction = pressed_direction(&mut actions);
+
+    let stick = stick_direction(&gamepads, &axes);
+    *stick_cooldown -= time.delta_seconds();
+    if stick == Vec2::ZERO {
+        *stick_cooldown = 0.0;
+    } else if *stick_cooldown <= 0.0 {
+        *stick_cooldown = STICK_REPEAT;
+        direction += if stick.x.abs() > stick.y.abs() {
+            IVec2::new(stick.x.signum() as i32, 0)
+        } else {
+            IVec2::new(0, stick.y.signum() as i32)
+        };
+    }
+
+    if direction == IVec2::ZERO {
+        return;
+    }
+
+    // The first press picks the first chest, the following ones move from there
+    let newpos = match hovered_chest.0 {
+        Some((x, y)) => (
+            (x + direction.x).clamp(0, layout.columns - 1),
+            (y + direction.y).clamp(0, layout.rows - 1),
+        ),
+        None => (0, 0),
+    };
+
+    pointer_light.single_mut().translation = layout.chest_translation(newpos) + Vec3::Z * 3.0;
+
+    hover(
+        &mut commands,
+        &mut hovered_chest,
+        Some(newpos),
+        &chests,
+        &objects,
+        simulation.animation_speed(),
+    );
+}
+
+fn confirm(
+    mut actions: Actions,
+    mut instructions: ResMut<Instructions>,
+    mut hovered_chest: ResMut<HoveredChest>,
+) {
+    if instructions.0.is_empty() && actions.just_pressed(Action::Confirm) {
+        select_hovered(&mut instructions, &mut hovered_chest);
+    }
+}
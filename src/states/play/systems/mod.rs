@@ -1,55 +1,116 @@
-use std::{f32::consts::PI, iter};
+use std::f32::consts::PI;
 
-use bevy::{input::mouse::MouseButtonInput, pbr::AmbientLight, prelude::*, render::camera::Camera};
+use bevy::{
+    pbr::AmbientLight, prelude::*, render::camera::Camera, utils::HashSet, window::CursorMoved,
+};
 use bevy_egui::{
-    egui::{self, Color32, RichText},
+    egui::{
+        self,
+        text::{Fonts, LayoutJob},
+        Color32, FontId, RichText, TextFormat,
+    },
     EguiContexts,
 };
-use bevy_tweening::Animator;
 
-use crate::plugins::post_process::PostProcessCamera;
+use crate::plugins::{
+    input::{Action, Actions},
+    locale::{Arg, Message, Texts},
+    post_process::{PostEffect, PostProcessCamera, PostProcessStack, RenderScale},
+    settings::{NewsPalette, Settings},
+};
 
 use super::{
-    components::{PointerLight, Rotate},
-    events::{NewsFeedUpdate, NewsLevel},
-    resources::{AssetList, Chests, HoveredChest, Instructions, NewsFeed},
-    utils::{self, item::Item},
-    GameState,
+    components::{PlayScoped, PointerLight, Rotate},
+    events::{NewsCategory, NewsFeedUpdate, NewsLevel, SaveGame},
+    resources::{
+        self, AssetList, Chests, GameRng, HoveredChest, Instructions, NewsEntry, NewsFeed, Notes,
+    },
+    save,
+    simulation::{LosingConditions, Simulation},
+    status_effects::StatusEffect,
+    utils::{
+        self,
+        item::Catalogue,
+        layout::{Layout, LayoutPresets},
+        sound_bank::Sounds,
+    },
+    GameState, PauseState, Unpaused,
 };
 
+mod game_over;
+mod grid_cursor;
 pub mod instructions;
+mod music;
+mod pause;
 
 use instructions::Instruction;
 
 pub fn add_systems(app: &mut App) {
     instructions::add_instruction_systems(app);
+    game_over::add_game_over_systems(app);
+    pause::add_pause_systems(app);
+    music::add_music_systems(app);
+    grid_cursor::add_grid_cursor_systems(app);
 
-    app.add_system(set_default_font.in_schedule(OnEnter(GameState::Play)))
-        .add_system(spawn_level.in_schedule(OnEnter(GameState::Play)))
+    app.add_system(spawn_level.in_schedule(OnEnter(GameState::Play)))
         .add_system(initialize_newsfeed.in_schedule(OnEnter(GameState::Play)))
-        .add_system(update_newsfeed.in_set(OnUpdate(GameState::Play)))
+        // A continued run has to be in place before the level and the news are set up
+        .add_system(
+            save::continue_run
+                .before(spawn_level)
+                .before(initialize_newsfeed)
+                .in_schedule(OnEnter(GameState::Play)),
+        )
+        .add_system(despawn_level.in_schedule(OnExit(GameState::Play)))
+        .add_system(resources::reset_resources.in_schedule(OnExit(GameState::Play)))
+        // Before the game is saved or left, so that the news of the last step are kept
+        .add_system(
+            update_newsfeed
+                .after(Unpaused)
+                .in_set(OnUpdate(GameState::Play)),
+        )
         .add_system(write_newsfeed.in_set(OnUpdate(GameState::Play)))
-        .add_system(mouse_move.in_set(OnUpdate(GameState::Play)))
-        .add_system(mouse_click.in_set(OnUpdate(GameState::Play)))
-        .add_system(rotate.in_set(OnUpdate(GameState::Play)));
-}
-
-fn set_default_font(mut contexts: EguiContexts) {
-    let ctx = contexts.ctx_mut();
-    let mut style = (*ctx.style()).clone();
-
-    *style.text_styles.get_mut(&egui::TextStyle::Body).unwrap() =
-        egui::FontId::new(18.0, egui::FontFamily::Proportional);
-
-    ctx.set_style(style);
+        .add_system(
+            write_hud
+                .after(write_newsfeed)
+                .in_set(OnUpdate(GameState::Play)),
+        )
+        .add_system(write_notes.in_set(OnUpdate(GameState::Play)))
+        .add_system(toggle_notes.in_set(Unpaused))
+        .add_system(mouse_move.in_set(Unpaused))
+        .add_system(rotate.in_set(Unpaused))
+        .add_system(
+            save::save_game
+                .after(update_newsfeed)
+                .in_set(OnUpdate(GameState::Play)),
+        );
 }
 
+#[allow(clippy::too_many_arguments)]
 fn spawn_level(
     mut commands: Commands,
     assets: Res<AssetList>,
     mut ambient_light: ResMut<AmbientLight>,
     mut chests: ResMut<Chests>,
+    mut simulation: ResMut<Simulation>,
+    mut layout: ResMut<Layout>,
+    layouts: Res<Assets<LayoutPresets>>,
+    catalogue: Catalogue,
+    mut post_process_stack: ResMut<PostProcessStack>,
 ) {
+    // A continued game arrives with its board, its chests already stocked and its losing
+    // conditions
+    if simulation.chests.is_empty() {
+        *layout = layouts.get(&assets.layouts).unwrap().chosen();
+        simulation.losing_conditions = LosingConditions::from_args();
+        simulation.stock(catalogue.get(), &layout);
+    }
+
+    let lights_out = simulation.lights_out();
+    for (effect, strength) in post_effects(&simulation) {
+        post_process_stack.set(effect, strength);
+    }
+
     commands
         .spawn(PointLightBundle {
             point_light: PointLight {
@@ -64,22 +125,27 @@ fn spawn_level(
         })
         .insert((
             Camera3dBundle {
-                transform: *utils::CAMERA_REST_POS,
+                transform: layout.camera_rest(),
                 ..default()
             },
             PostProcessCamera,
+            PlayScoped,
         ));
 
     commands
         .spawn(PointLightBundle {
             point_light: PointLight {
-                intensity: 0.0,
+                intensity: if lights_out {
+                    utils::POINTER_LIGHT.intensity
+                } else {
+                    0.0
+                },
                 ..*utils::POINTER_LIGHT
             },
             transform: Transform::from_xyz(-1000.0, -1000.0, 1000.0),
             ..default()
         })
-        .insert(PointerLight);
+        .insert((PointerLight, PlayScoped));
 
     ambient_light.color = Color::WHITE;
     ambient_light.brightness = 0.10;
@@ -96,11 +162,20 @@ fn spawn_level(
     ];
 
     for pos in light_pos {
-        commands.spawn(PointLightBundle {
-            point_light: *utils::POINT_LIGHT,
-            transform: Transform::from_translation(pos),
-            ..default()
-        });
+        commands
+            .spawn(PointLightBundle {
+                point_light: PointLight {
+                    intensity: if lights_out {
+                        0.0
+                    } else {
+                        utils::POINT_LIGHT.intensity
+                    },
+                    ..*utils::POINT_LIGHT
+                },
+                transform: Transform::from_translation(pos),
+                ..default()
+            })
+            .insert(PlayScoped);
     }
 
     commands
@@ -108,101 +183,373 @@ fn spawn_level(
             scene: assets.level.clone(),
             ..default()
         })
-        .insert(Name::new("Level"));
-
-    let mut items: Vec<Item> = iter::once(Item::Barrel)
-        .cycle()
-        .take(2)
-        .chain(iter::once(Item::Burger).cycle().take(6))
-        .chain(iter::once(Item::Gun).cycle().take(3))
-        .chain(iter::once(Item::Pill).cycle().take(3))
-        .chain(iter::once(Item::Screwdriver).cycle().take(6))
-        .collect();
-
-    for x in 0..5 {
-        for y in 0..4 {
-            let id = commands
-                .spawn(SceneBundle {
-                    scene: assets.chest.clone(),
-                    transform: Transform::from_xyz(
-                        -2.4 + x as f32 * 1.2,
-                        -1.8 + y as f32 * 1.2,
-                        0.0,
-                    ),
-                    ..default()
-                })
-                .id();
-
-            chests.0.insert((x, y), (id, items.pop().unwrap()));
+        .insert((Name::new("Level"), PlayScoped));
+
+    for pos in layout.positions() {
+        let id = commands
+            .spawn(SceneBundle {
+                scene: assets.chest.clone(),
+                transform: Transform::from_translation(layout.chest_translation(pos)),
+                ..default()
+            })
+            .insert(PlayScoped)
+            .id();
+
+        chests.0.insert(pos, id);
+    }
+}
+
+fn despawn_level(
+    mut commands: Commands,
+    entities: Query<Entity, With<PlayScoped>>,
+    mut post_process_stack: ResMut<PostProcessStack>,
+) {
+    for entity in entities.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    // The accessibility filters stay, they belong to the settings
+    for (effect, _) in post_effects(&Simulation::default()) {
+        post_process_stack.set(effect, 0.0);
+    }
+}
+
+// How strongly each status effect shows on screen
+pub fn post_effects(simulation: &Simulation) -> Vec<(PostEffect, f32)> {
+    StatusEffect::ALL
+        .iter()
+        .filter_map(|effect| {
+            let (post_effect, strength) = effect.behaviour().post_effect(simulation)?;
+            let strength = if !simulation.status_effects.contains_key(effect) {
+                0.0
+            } else if simulation.is_amplified(*effect) {
+                strength * 1.6
+            } else {
+                strength
+            };
+            Some((post_effect, strength))
+        })
+        .collect()
+}
+
+fn initialize_newsfeed(
+    mut ev_news: EventWriter<NewsFeedUpdate>,
+    news_feed: Res<NewsFeed>,
+    simulation: Res<Simulation>,
+) {
+    if !news_feed.0.is_empty() {
+        return;
+    }
+
+    ev_news.send(NewsFeedUpdate::new(
+        NewsLevel::External,
+        NewsCategory::Headline,
+        Message::new("news.intro.flash"),
+    ));
+    ev_news.send(NewsFeedUpdate::new(
+        NewsLevel::Event,
+        NewsCategory::Story,
+        Message::new("news.intro.tutorial").with("money", simulation.money),
+    ));
+}
+
+// News are stamped with the customer being served when they arrive
+fn update_newsfeed(
+    mut ev_news: EventReader<NewsFeedUpdate>,
+    mut news_feed: ResMut<NewsFeed>,
+    simulation: Res<Simulation>,
+) {
+    // Untouched without news, so that the news panel only rebuilds its lines for new ones
+    if ev_news.is_empty() {
+        return;
+    }
+
+    news_feed.0.extend(ev_news.iter().map(|news| NewsEntry {
+        turn: simulation.customer_no,
+        balance: simulation.money,
+        news: news.clone(),
+    }));
+}
+
+struct NewsFilter {
+    levels: HashSet<NewsLevel>,
+    search: String,
+}
+
+impl Default for NewsFilter {
+    fn default() -> Self {
+        NewsFilter {
+            levels: NewsLevel::ALL.into_iter().collect(),
+            search: String::new(),
         }
     }
 }
 
-fn initialize_newsfeed(mut ev_news: EventWriter<NewsFeedUpdate>) {
-    ev_news.send(NewsFeedUpdate(NewsLevel::External, "News Flash: The local junkyard has a new owner! Maybe now our little town will forget the tragic demise of the previous owner...".to_string()));
-    ev_news.send(NewsFeedUpdate(NewsLevel::Event, "It is my honor to welcome my new employer! My name is Trevor Utorial! With any luck, we can redouble the $1000 in your pocket within three days! We should probably write down the inventory before opening shop! Bring a crate to the front to see what is inside!".to_string()));
+// The news that pass the filter, translated and broken into lines, so that only the visible
+// lines are laid out each frame. Rebuilt when the feed, the filter, the settings or the width of
+// the panel change.
+#[derive(Default)]
+struct NewsLines {
+    lines: Vec<LayoutJob>,
+    width: f32,
 }
 
-fn update_newsfeed(mut ev_news: EventReader<NewsFeedUpdate>, mut news_feed: ResMut<NewsFeed>) {
-    const MAX_LEN: usize = 20;
-    for NewsFeedUpdate(level, text) in ev_news.iter() {
-        let color = match level {
-            NewsLevel::External => Color32::GRAY,
-            NewsLevel::Event => Color32::WHITE,
-            NewsLevel::Correct => Color32::GREEN,
-            NewsLevel::Wrong => Color32::RED,
+fn news_lines(
+    newsfeed: &NewsFeed,
+    filter: &NewsFilter,
+    palette: &NewsPalette,
+    texts: &Texts,
+    fonts: &Fonts,
+    font_id: &FontId,
+    width: f32,
+) -> Vec<LayoutJob> {
+    let search = filter.search.to_lowercase();
+    let mut lines = Vec::new();
+
+    for entry in newsfeed.0.iter() {
+        let news = &entry.news;
+        if !filter.levels.contains(&news.level) {
+            continue;
+        }
+
+        let text = texts.message(&news.message);
+        if !search.is_empty() && !text.to_lowercase().contains(&search) {
+            continue;
+        }
+
+        let color = match news.level {
+            NewsLevel::External => palette.headline,
+            NewsLevel::Event => palette.event,
+            NewsLevel::Correct => palette.good,
+            NewsLevel::Wrong => palette.bad,
         };
 
-        news_feed.0.push_back(RichText::new(text).color(color));
-    }
+        let stamp = Message::new("news.stamp").with("turn", entry.turn);
+        let mut parts = vec![
+            (texts.message(&stamp), palette.muted),
+            (news.level.marker().to_string(), color),
+            (text, color),
+        ];
+        if let Some(delta) = news.money_delta {
+            let (sign, color) = if delta.value() < 0 {
+                ("", palette.bad)
+            } else {
+                ("+", palette.good)
+            };
+            parts.push((format!("{sign}{}", texts.money(delta.value())), color));
+        }
 
-    let len = news_feed.0.len();
-    if len > MAX_LEN {
-        news_feed.0.drain(0..(len - MAX_LEN));
+        // Word by word, starting a new line whenever the next word does not fit
+        let mut line = LayoutJob::default();
+        let mut line_width = 0.0;
+        for (part, color) in parts {
+            let format = TextFormat::simple(font_id.clone(), color);
+            for word in format!("{part} ").split_inclusive(' ') {
+                let word_width: f32 = word.chars().map(|c| fonts.glyph_width(font_id, c)).sum();
+                if line_width > 0.0 && line_width + word_width > width {
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0.0;
+                }
+                line.append(word, 0.0, format.clone());
+                line_width += word_width;
+            }
+        }
+        lines.push(line);
     }
+
+    lines
 }
 
+#[allow(clippy::too_many_arguments)]
 fn write_newsfeed(
     mut contexts: EguiContexts,
-    ev_news: EventReader<NewsFeedUpdate>,
     newsfeed: Res<NewsFeed>,
+    mut filter: Local<NewsFilter>,
+    mut news_lines_cache: Local<NewsLines>,
+    settings: Res<Settings>,
+    texts: Texts,
 ) {
     let ctx = contexts.ctx_mut();
+    let palette = settings.news_theme.palette();
 
     egui::TopBottomPanel::bottom("bottom_panel")
         .height_range(50.0..=300.0)
         .default_height(150.0)
         .resizable(true)
         .show(ctx, |ui| {
+            let mut filter_changed = false;
+            ui.horizontal(|ui| {
+                for level in NewsLevel::ALL {
+                    let mut shown = filter.levels.contains(&level);
+                    if ui
+                        .toggle_value(
+                            &mut shown,
+                            format!("{} {}", level.marker(), texts.get(level.key())),
+                        )
+                        .changed()
+                    {
+                        if shown {
+                            filter.levels.insert(level);
+                        } else {
+                            filter.levels.remove(&level);
+                        }
+                        filter_changed = true;
+                    }
+                }
+                ui.separator();
+                filter_changed |= ui
+                    .add(
+                        egui::TextEdit::singleline(&mut filter.search)
+                            .hint_text(texts.get("news.search")),
+                    )
+                    .changed();
+            });
+            ui.separator();
+
+            let spacing = ui.spacing();
+            let width = ui.available_width()
+                - spacing.scroll_bar_width
+                - spacing.scroll_bar_inner_margin
+                - spacing.scroll_bar_outer_margin;
+            let font_id = egui::TextStyle::Body.resolve(ui.style());
+
+            // The language and the colours are part of the settings
+            if newsfeed.is_changed()
+                || settings.is_changed()
+                || filter_changed
+                || news_lines_cache.width != width
+            {
+                let lines = ui.fonts(|fonts| {
+                    news_lines(&newsfeed, &filter, &palette, &texts, fonts, &font_id, width)
+                });
+                *news_lines_cache = NewsLines { lines, width };
+            }
+
+            // Follows the latest news, unless scrolled up to read older ones
+            let row_height = ui.text_style_height(&egui::TextStyle::Body);
+            let lines = &news_lines_cache.lines;
             egui::ScrollArea::vertical()
                 .auto_shrink([false, false])
-                .show(ui, |ui| {
-                    for text in newsfeed.0.iter() {
-                        let response = ui.label(text.clone());
-                        if !ev_news.is_empty() {
-                            response.scroll_to_me(None);
-                        }
+                .stick_to_bottom(true)
+                .show_rows(ui, row_height, lines.len(), |ui, range| {
+                    for line in &lines[range] {
+                        ui.add(egui::Label::new(line.clone()).wrap(false));
                     }
                 });
         });
 }
 
-fn mouse_click(
-    mut instructions: ResMut<Instructions>,
-    mut hovered_chest: ResMut<HoveredChest>,
-    mut mouse_button_input_events: EventReader<MouseButtonInput>,
+// The balance, the current customer and every active status effect with its countdown. It
+// reads the simulation each frame, so it follows the effects as they tick.
+#[allow(clippy::too_many_arguments)]
+fn write_hud(
+    mut contexts: EguiContexts,
+    mut simulation: ResMut<Simulation>,
+    instructions: Res<Instructions>,
+    mut ev_news: EventWriter<NewsFeedUpdate>,
+    mut ev_save: EventWriter<SaveGame>,
+    mut sounds: Sounds,
+    rng: Res<GameRng>,
+    texts: Texts,
+    pause_state: Res<State<PauseState>>,
 ) {
-    if !instructions.0.is_empty() {
-        return;
+    let ctx = contexts.ctx_mut();
+
+    egui::SidePanel::right("hud")
+        .resizable(false)
+        .show(ctx, |ui| {
+            let money = Message::new("hud.money").with("money", simulation.money);
+            ui.heading(texts.message(&money));
+            let customer = Message::new("hud.customer").with("customer", simulation.customer_no);
+            ui.label(texts.message(&customer));
+            ui.separator();
+
+            let active: Vec<_> = StatusEffect::ALL
+                .iter()
+                .copied()
+                .filter(|effect| simulation.status_effects.contains_key(effect))
+                .collect();
+            if active.is_empty() {
+                ui.label(RichText::new(texts.get("status.none")).color(Color32::GRAY));
+            }
+            for effect in active {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(effect.icon()).size(28.0));
+                    ui.vertical(|ui| {
+                        ui.label(RichText::new(texts.get(effect.name_key())).strong());
+                        ui.label(texts.get(effect.description_key()));
+                        if let Some(turns) = simulation.remaining_turns(effect) {
+                            let countdown = Message::new("status.turns_left").with("turns", turns);
+                            ui.label(RichText::new(texts.message(&countdown)).color(Color32::GRAY));
+                        }
+                        if let Some(details) = effect.behaviour().details(&simulation) {
+                            ui.label(texts.message(&details));
+                        }
+                        if let Some(cost) = simulation.treatment_cost(effect) {
+                            // Only between turns, so the treatment does not change a running one,
+                            // and not while the game is paused
+                            let treatment = Message::new("status.treatment").with("cost", cost);
+                            let between_turns =
+                                instructions.0.is_empty() && pause_state.0 == PauseState::Running;
+                            let button = ui.add_enabled(
+                                between_turns,
+                                egui::Button::new(texts.message(&treatment)),
+                            );
+                            if button.clicked() {
+                                let report = simulation.buy_treatment(effect);
+                                instructions::present(report, &mut ev_news, &mut sounds);
+                                ev_save.send(SaveGame);
+                            }
+                        }
+                    });
+                });
+            }
+
+            ui.with_layout(egui::Layout::bottom_up(egui::Align::Min), |ui| {
+                let seed = Message::new("hud.seed").with("seed", Arg::Text(rng.seed.to_string()));
+                ui.label(RichText::new(texts.message(&seed)).color(Color32::GRAY));
+            });
+        });
+}
+
+fn toggle_notes(mut actions: Actions, mut notes: ResMut<Notes>) {
+    if actions.just_pressed(Action::ToggleNotes) {
+        notes.open = !notes.open;
     }
+}
 
-    if !mouse_button_input_events
-        .iter()
-        .any(|evt| evt.button == MouseButton::Left && evt.state.is_pressed())
-    {
+// One text field per chest, laid out like the board with the first row at the bottom
+fn write_notes(
+    mut contexts: EguiContexts,
+    mut notes: ResMut<Notes>,
+    layout: Res<Layout>,
+    texts: Texts,
+) {
+    if !notes.open {
         return;
     }
 
+    let ctx = contexts.ctx_mut();
+
+    egui::Window::new(texts.get("notes.title"))
+        .id(egui::Id::new("notes"))
+        .anchor(egui::Align2::LEFT_TOP, [10.0, 10.0])
+        .resizable(false)
+        .show(ctx, |ui| {
+            egui::Grid::new("notes_grid").show(ui, |ui| {
+                for y in (0..layout.rows).rev() {
+                    for x in 0..layout.columns {
+                        let entry = notes.entries.entry((x, y)).or_default();
+                        ui.add(egui::TextEdit::singleline(entry).desired_width(70.0));
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+}
+
+// Starts a turn with the hovered chest, if any
+fn select_hovered(instructions: &mut Instructions, hovered_chest: &mut HoveredChest) {
     if let Some(pos) = hovered_chest.0 {
         instructions.0.push_back(Instruction::SwapWithFirst(pos));
         instructions.0.push_back(Instruction::CameraToFirstChest);
@@ -216,18 +563,57 @@ fn mouse_click(
     }
 }
 
+// Lowers the previously hovered chest and lifts the new one
+fn hover(
+    commands: &mut Commands,
+    hovered_chest: &mut HoveredChest,
+    newpos: Option<(i32, i32)>,
+    chests: &Chests,
+    objects: &Query<&mut Transform, Without<PointerLight>>,
+    speed: f32,
+) {
+    if hovered_chest.0 == newpos {
+        return;
+    }
+
+    if let Some(pos) = hovered_chest.0 {
+        let old_chest = chests.0[&pos];
+        let start = objects.get(old_chest).unwrap().translation;
+        commands.entity(old_chest).insert(utils::tween::animator(
+            utils::tween::lift(start, 0.0, 100),
+            speed,
+        ));
+    }
+
+    if let Some(pos) = newpos {
+        let new_chest = chests.0[&pos];
+        let start = objects.get(new_chest).unwrap().translation;
+        commands.entity(new_chest).insert(utils::tween::animator(
+            utils::tween::lift(start, 0.5, 100),
+            speed,
+        ));
+    }
+
+    hovered_chest.0 = newpos;
+}
+
 #[allow(clippy::too_many_arguments)]
 fn mouse_move(
     mut commands: Commands,
+    mut ev_cursor: EventReader<CursorMoved>,
     windows: Query<&Window>,
     cameras: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
     mut hovered_chest: ResMut<HoveredChest>,
     chests: Res<Chests>,
-    mut objects: Query<&mut Transform, Without<PointerLight>>,
+    objects: Query<&mut Transform, Without<PointerLight>>,
     instructions: Res<Instructions>,
     mut pointer_light: Query<&mut Transform, With<PointerLight>>,
+    layout: Res<Layout>,
+    render_scale: Res<RenderScale>,
+    simulation: Res<Simulation>,
 ) {
-    if !instructions.0.is_empty() {
+    // A resting mouse leaves the selection to the keyboard and gamepad
+    if ev_cursor.iter().last().is_none() || !instructions.0.is_empty() {
         return;
     }
 
@@ -236,6 +622,8 @@ fn mouse_move(
 
     let Some(position) = window.cursor_position() else { return; };
 
+    // The camera renders to the offscreen image, not the window
+    let position = render_scale.target_position(window, position);
     let Some(ray) = camera.0.viewport_to_world(camera.1, position) else { return; };
     let Some(dist) = ray.intersect_plane(Vec3::new(0.0, 0.0, 1.0), Vec3::Z) else { return; };
 
@@ -243,38 +631,14 @@ fn mouse_move(
 
     pointer_light.single_mut().translation = Vec3 { z: 3.0, ..point };
 
-    if (-2.9..2.9).contains(&point.x) && (-2.3..2.3).contains(&point.y) {
-        let newpos = (
-            ((point.x + 2.9) / 1.2) as i32,
-            ((point.y + 2.3) / 1.2) as i32,
-        );
-
-        if let Some(pos) = hovered_chest.0 {
-            if pos == newpos {
-                return;
-            }
-            let old_chest = chests.0[&pos].0;
-            let start = objects.get_mut(old_chest).unwrap().translation;
-            commands
-                .entity(old_chest)
-                .insert(Animator::new(utils::tween::lift(start, 0.0, 100)));
-        }
-
-        let new_chest = chests.0[&newpos].0;
-        let start = objects.get(new_chest).unwrap().translation;
-        commands
-            .entity(new_chest)
-            .insert(Animator::new(utils::tween::lift(start, 0.5, 100)));
-
-        hovered_chest.0 = Some(newpos);
-    } else if let Some(pos) = hovered_chest.0 {
-        let old_chest = chests.0[&pos].0;
-        let start = objects.get_mut(old_chest).unwrap().translation;
-        commands
-            .entity(old_chest)
-            .insert(Animator::new(utils::tween::lift(start, 0.0, 100)));
-        hovered_chest.0 = None;
-    }
+    hover(
+        &mut commands,
+        &mut hovered_chest,
+        layout.chest_at(point),
+        &chests,
+        &objects,
+        simulation.animation_speed(),
+    );
 }
 
 fn rotate(mut query: Query<&mut Transform, With<Rotate>>, time: Res<Time>) {
@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+
+use crate::{
+    plugins::audio::Channels,
+    states::{
+        play::{
+            events::DuckMusic,
+            resources::{AssetList, MusicState},
+            simulation::Simulation,
+            utils::sound_bank::{MusicLayer, SoundBank},
+            Unpaused,
+        },
+        GameState,
+    },
+};
+
+// Below this balance the shop turns tense
+const TENSE_BALANCE: i32 = 0;
+const DUCKED_VOLUME: f64 = 0.3;
+const CROSSFADE: Duration = Duration::from_millis(1500);
+
+pub fn add_music_systems(app: &mut App) {
+    app.add_system(start_music.in_schedule(OnEnter(GameState::Play)))
+        .add_system(update_music.in_set(Unpaused))
+        .add_system(stop_music.in_schedule(OnExit(GameState::Play)));
+}
+
+// Every layer starts silent at the same time so that crossfades stay in sync
+fn start_music(
+    mut music: ResMut<MusicState>,
+    audio: Channels,
+    assets: Res<AssetList>,
+    banks: Res<Assets<SoundBank>>,
+) {
+    let bank = banks.get(&assets.sounds).unwrap();
+
+    for (layer, def) in bank.music.iter() {
+        let instance = audio
+            .music
+            .play(def.handle.clone())
+            .looped()
+            .with_volume(0.0)
+            .handle();
+        music.layers.insert(*layer, instance);
+    }
+}
+
+fn target_layer(simulation: &Simulation) -> MusicLayer {
+    if simulation.lights_out() {
+        MusicLayer::Muffled
+    } else if simulation.war || simulation.money.value() < TENSE_BALANCE {
+        MusicLayer::Tense
+    } else {
+        MusicLayer::Calm
+    }
+}
+
+fn update_music(
+    mut music: ResMut<MusicState>,
+    mut ev_duck: EventReader<DuckMusic>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+    simulation: Res<Simulation>,
+    assets: Res<AssetList>,
+    banks: Res<Assets<SoundBank>>,
+    time: Res<Time>,
+) {
+    let bank = banks.get(&assets.sounds).unwrap();
+
+    for DuckMusic(seconds) in ev_duck.iter() {
+        music.duck_remaining = music.duck_remaining.max(*seconds);
+    }
+    music.duck_remaining = (music.duck_remaining - time.delta_seconds()).max(0.0);
+
+    // Fall back to the calm layer when the bank has no music for the mood
+    let mut target = target_layer(&simulation);
+    if !bank.music.contains_key(&target) {
+        target = MusicLayer::Calm;
+    }
+    let ducked = music.duck_remaining > 0.0;
+
+    // Instances only exist once the audio plugin has started them, until then keep retrying
+    let started = music
+        .layers
+        .values()
+        .all(|instance| instances.contains(instance));
+    if !started || music.applied == Some((target, ducked)) {
+        return;
+    }
+    music.applied = Some((target, ducked));
+
+    for (layer, instance) in music.layers.iter() {
+        let instance = instances.get_mut(instance).unwrap();
+
+        let volume = match bank.music.get(layer) {
+            Some(def) if *layer == target && ducked => def.volume * DUCKED_VOLUME,
+            Some(def) if *layer == target => def.volume,
+            _ => 0.0,
+        };
+        instance.set_volume(volume, AudioTween::linear(CROSSFADE));
+    }
+}
+
+fn stop_music(audio: Channels) {
+    audio.music.stop();
+}
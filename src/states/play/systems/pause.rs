@@ -0,0 +1,150 @@
+use bevy::prelude::*;
+use bevy_egui::{
+    egui::{self, Align, Label, Layout, RichText, Sense},
+    EguiContexts,
+};
+use bevy_tweening::{Animator, AnimatorState};
+
+use crate::{
+    plugins::{
+        audio::Channels,
+        input::{Action, Actions},
+        locale::Texts,
+        settings::{Settings, SettingsWindow},
+    },
+    states::{
+        play::{
+            export,
+            resources::{self, ExportOutcome, GameRng, NewsFeed},
+        },
+        GameState, PauseState,
+    },
+};
+
+pub fn add_pause_systems(app: &mut App) {
+    app.add_system(toggle_pause.in_set(OnUpdate(GameState::Play)))
+        .add_system(pause_menu.in_set(OnUpdate(PauseState::Paused)))
+        .add_system(freeze.in_schedule(OnEnter(PauseState::Paused)))
+        .add_system(resources::clear_export_outcome.in_schedule(OnEnter(PauseState::Paused)))
+        .add_system(unfreeze.in_schedule(OnExit(PauseState::Paused)));
+}
+
+fn toggle_pause(
+    mut actions: Actions,
+    pause_state: Res<State<PauseState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+    settings_window: Res<SettingsWindow>,
+) {
+    // The settings window may be waiting for a key to bind
+    if settings_window.open || !actions.just_pressed(Action::Pause) {
+        return;
+    }
+
+    next_pause_state.set(match pause_state.0 {
+        PauseState::Running => PauseState::Paused,
+        PauseState::Paused => PauseState::Running,
+    });
+}
+
+// Waits and the unpaused systems are stopped by the Unpaused set, tweens and sounds are not
+fn freeze(mut animators: Query<&mut Animator<Transform>>, audio: Channels) {
+    for mut animator in animators.iter_mut() {
+        animator.state = AnimatorState::Paused;
+    }
+    audio.pause();
+}
+
+fn unfreeze(mut animators: Query<&mut Animator<Transform>>, audio: Channels) {
+    for mut animator in animators.iter_mut() {
+        animator.state = AnimatorState::Playing;
+    }
+    audio.resume();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn pause_menu(
+    mut contexts: EguiContexts,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+    audio: Channels,
+    mut settings_window: ResMut<SettingsWindow>,
+    texts: Texts,
+    settings: Res<Settings>,
+    news_feed: Res<NewsFeed>,
+    rng: Res<GameRng>,
+    mut export_outcome: ResMut<ExportOutcome>,
+) {
+    if settings_window.open {
+        return;
+    }
+
+    let ctx = contexts.ctx_mut();
+
+    egui::Window::new("pause")
+        .title_bar(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.with_layout(Layout::top_down(Align::Center), |ui| {
+                ui.heading(RichText::new(texts.get("pause.title")).size(63.0));
+                ui.add_space(20.0);
+
+                if ui
+                    .add(
+                        Label::new(RichText::new(texts.get("pause.resume")).size(42.0))
+                            .sense(Sense::click()),
+                    )
+                    .clicked()
+                {
+                    next_pause_state.set(PauseState::Running);
+                }
+                if ui
+                    .add(
+                        Label::new(RichText::new(texts.get("menu.restart")).size(42.0))
+                            .sense(Sense::click()),
+                    )
+                    .clicked()
+                {
+                    audio.stop();
+                    next_pause_state.set(PauseState::Running);
+                    next_state.set(GameState::LoadPlay);
+                }
+                if ui
+                    .add(
+                        Label::new(RichText::new(texts.get("menu.settings")).size(42.0))
+                            .sense(Sense::click()),
+                    )
+                    .clicked()
+                {
+                    settings_window.open = true;
+                }
+                if ui
+                    .add(
+                        Label::new(RichText::new(texts.get("menu.export")).size(42.0))
+                            .sense(Sense::click()),
+                    )
+                    .clicked()
+                {
+                    let result =
+                        export::export(&news_feed, rng.seed, None, settings.export_format, &texts);
+                    export_outcome.0 = Some(export::outcome(&result));
+                }
+                if ui
+                    .add(
+                        Label::new(RichText::new(texts.get("menu.main_menu")).size(42.0))
+                            .sense(Sense::click()),
+                    )
+                    .clicked()
+                {
+                    audio.stop();
+                    next_pause_state.set(PauseState::Running);
+                    next_state.set(GameState::MainMenu);
+                }
+
+                if let Some(outcome) = export_outcome.0.as_ref() {
+                    ui.add_space(20.0);
+                    ui.label(texts.message(outcome));
+                }
+            });
+        });
+}
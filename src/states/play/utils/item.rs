@@ -1,249 +1,223 @@
-use bevy::utils::HashSet;
+use std::ops::Index;
 
+use bevy::{
+    asset::{AssetLoader, AssetPath, BoxedFuture, LoadContext, LoadedAsset},
+    ecs::system::SystemParam,
+    prelude::*,
+    reflect::TypeUuid,
+};
 use rand::{prelude::*, seq::SliceRandom};
+use serde::Deserialize;
+
+use crate::plugins::locale::Message;
 
 use super::{
-    super::{
-        resources::{Money, Sound},
-        utils::StatusEffect,
-    },
+    super::resources::{AssetList, Money},
     SideEffect,
 };
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
-pub enum Item {
-    Barrel,
-    Burger,
-    Gun,
-    Pill,
-    Screwdriver,
+// An index into the catalogue, only meaningful while the game runs; saves refer to items by id
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Item(usize);
+
+#[derive(Deserialize)]
+pub struct TurnRange {
+    pub from: i32,
+    #[serde(default)]
+    pub to: Option<i32>,
 }
 
-fn random_money(base: i32) -> Money {
-    Money::new(base + thread_rng().gen_range(-base / 20..=base / 20))
+impl TurnRange {
+    fn contains(&self, turn: i32) -> bool {
+        (self.from..=self.to.unwrap_or(i32::MAX)).contains(&turn)
+    }
 }
 
-impl Item {
-    pub fn new_random(turn: i32, prev_item: Option<Item>) -> Self {
-        use Item::*;
-        let items: &[Item] = match turn {
-            1..=5 => &[Burger, Screwdriver],
-            6..=20 => &[Burger, Screwdriver, Gun, Pill],
-            21 => &[Barrel],
-            _ => &[Burger, Screwdriver, Gun, Pill],
-        };
+fn default_weight() -> u32 {
+    1
+}
+
+#[derive(Deserialize)]
+pub struct SideEffectDef {
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    // String table key, with `{money}` standing for the money lost
+    #[serde(default)]
+    pub text: Option<String>,
+    pub effect: SideEffect,
+    // Cue names from the sound bank
+    #[serde(default)]
+    pub sounds: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ItemDef {
+    pub id: String,
+    pub model: String,
+    #[serde(skip)]
+    pub scene: Handle<Scene>,
+    pub gain: Money,
+    pub turns: Vec<TurnRange>,
+    // String table keys, one of their variants is picked each time
+    pub request: String,
+    pub found: String,
+    pub side_effects: Vec<SideEffectDef>,
+    #[serde(default)]
+    pub news: Option<String>,
+    // String table keys of the headlines that follow, in order
+    #[serde(default)]
+    pub war_news: Vec<String>,
+}
+
+#[derive(Deserialize, TypeUuid)]
+#[uuid = "8a3f9c2e-51d7-4b8e-9f0a-6c2d7e1b4a93"]
+pub struct ItemCatalogue {
+    pub tutorial_item: String,
+    pub items: Vec<ItemDef>,
+}
+
+#[derive(SystemParam)]
+pub struct Catalogue<'w> {
+    assets: Res<'w, AssetList>,
+    catalogues: Res<'w, Assets<ItemCatalogue>>,
+}
+
+impl<'w> Catalogue<'w> {
+    pub fn get(&self) -> &ItemCatalogue {
+        self.catalogues.get(&self.assets.items).unwrap()
+    }
+}
+
+#[derive(Default)]
+pub struct ItemCatalogueLoader;
+
+impl AssetLoader for ItemCatalogueLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let mut catalogue = ItemCatalogue::from_ron(bytes)?;
+
+            let mut dependencies = Vec::new();
+            for item in catalogue.items.iter_mut() {
+                let path: AssetPath<'static> = AssetPath::from(item.model.as_str()).to_owned();
+                item.scene = load_context.get_handle(path.clone());
+                dependencies.push(path);
+            }
+
+            load_context
+                .set_default_asset(LoadedAsset::new(catalogue).with_dependencies(dependencies));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["catalogue.ron"]
+    }
+}
+
+fn random_money(base: Money, rng: &mut impl Rng) -> Money {
+    let base = base.value();
+    Money::new(base + rng.gen_range(-base / 20..=base / 20))
+}
 
-        let mut set: HashSet<Item> = items.iter().copied().collect();
+impl ItemCatalogue {
+    pub fn from_ron(bytes: &[u8]) -> Result<Self, bevy::asset::Error> {
+        let catalogue: ItemCatalogue = ron::de::from_bytes(bytes)?;
 
-        if let Some(item) = prev_item {
-            set.remove(&item);
+        if catalogue.item(&catalogue.tutorial_item).is_none() {
+            return Err(bevy::asset::Error::msg(format!(
+                "tutorial item \"{}\" is not in the catalogue",
+                catalogue.tutorial_item
+            )));
+        }
+
+        for item in catalogue.items.iter() {
+            if item.side_effects.is_empty() {
+                return Err(bevy::asset::Error::msg(format!(
+                    "item \"{}\" needs at least one side effect",
+                    item.id
+                )));
+            }
         }
 
-        set.into_iter().choose(&mut thread_rng()).unwrap()
+        Ok(catalogue)
     }
 
-    pub fn request(&self) -> &'static str {
-        use Item::*;
-        let words: &[&'static str] = match self {
-            Barrel => &["a radioactive barrel", "a barrel with radioactive goo"],
-            Burger => &["a shipment of burgers", "food", "edibles"],
-            Gun => &["guns", "pieces that go bang bang", "weapons"],
-            Pill => &["a pill", "pills", "drugs"],
-            Screwdriver => &["a screwdriver", "screwdrivers"],
-        };
+    pub fn item(&self, id: &str) -> Option<Item> {
+        self.items.iter().position(|def| def.id == id).map(Item)
+    }
 
-        words.choose(&mut thread_rng()).unwrap()
+    pub fn tutorial_item(&self) -> Item {
+        self.item(&self.tutorial_item).unwrap()
     }
 
-    pub fn found(&self) -> &'static str {
-        use Item::*;
-        let words: &[&'static str] = match self {
-            Barrel => &[
-                "radioactive barrels",
-                "barrels with radioactive goo",
-                "barrels with the nuclear trefoil sign",
-            ],
-            Burger => &["burgers", "junk food", "food"],
-            Gun => &["guns", "pistols", "firearms"],
-            Pill => &["pills", "drugs", "medicine"],
-            Screwdriver => &["screwdrivers"],
-        };
+    pub fn new_random(&self, turn: i32, prev_item: Option<Item>, rng: &mut impl Rng) -> Item {
+        let items: Vec<Item> = (0..self.items.len())
+            .map(Item)
+            .filter(|item| self[*item].turns.iter().any(|range| range.contains(turn)))
+            .collect();
+
+        let choices: Vec<Item> = items
+            .iter()
+            .copied()
+            .filter(|item| Some(*item) != prev_item)
+            .collect();
+
+        choices
+            .choose(rng)
+            .or_else(|| items.choose(rng))
+            .copied()
+            .unwrap_or_else(|| self.tutorial_item())
+    }
+}
+
+impl Index<Item> for ItemCatalogue {
+    type Output = ItemDef;
 
-        words.choose(&mut thread_rng()).unwrap()
+    fn index(&self, item: Item) -> &ItemDef {
+        &self.items[item.0]
     }
+}
 
-    pub fn gain(&self) -> Money {
-        use Item::*;
-        let base = match self {
-            Barrel => 8_000_000,
-            Burger => 10,
-            Gun => 300,
-            Pill => 400,
-            Screwdriver => 10,
-        };
+impl ItemDef {
+    pub fn request(&self, rng: &mut impl Rng) -> Message {
+        Message::pick(&self.request, rng)
+    }
 
-        random_money(base)
+    pub fn found(&self, rng: &mut impl Rng) -> Message {
+        Message::pick(&self.found, rng)
     }
 
-    pub fn side_effect(&self) -> (String, SideEffect, Vec<Sound>) {
-        use Item::*;
-        use SideEffect::*;
-        use StatusEffect::*;
-
-        let mut rng = thread_rng();
-
-        match self {
-            Barrel => match rng.gen_range(0..5) {
-                0 => ("".to_string(), ToggleCancer, vec![Sound::Strange]),
-                1 => {
-                    let money = random_money(200);
-                    (
-                        format!("You got scared and had to eat all the iodine tablets, didn't you? Restocking cost you {money}!"),
-                        MoneyLoss(money),
-                        vec![Sound::Eat]
-                    )
-                },
-                2 => (
-                    "The government raided the junkyard to find any more runaway radioactives! They sure left a mess and moved everything around!".to_string(),
-                    StatusEffectEnable(Reshuffle, 1),
-                    vec![Sound::Siren]
-                ),
-                3 => {
-                    let money = random_money(2000);
-                    (
-                        format!("You had to go to the ER with severe radiation positioning. You are ok now, but the bill was {money}!"),
-                        MoneyLoss(money),
-                        vec![Sound::SadTrombone]
-                    )
-                },
-                4 => (
-                    "The radioactive goo spilled and made a mess! Luckily the customer helped you clean up, before promptly dying from radiation poisoning.".to_string(),
-                    CustomerKill,
-                    vec![Sound::Death]
-                ),
-                _ => panic!()
-            },
-            Burger => match rng.gen_range(0..4) {
-                0 => (
-                    r#"You asked yourself, "what could go wrong" and ate the burger. That was when you felt your stomach slowly turning upside down."#.to_string(),
-                    StatusEffectEnable(Diarrhea, 3),
-                    vec![Sound::Eat, Sound::Fart]
-                ),
-                1 => {
-                    let money = random_money(500);
-                    (
-                        format!("Clearly, a bite won't hurt? After a severe food poisoning, the hospital thinks otherwise. Your idiocy cost {money}."),
-                        MoneyLoss(money),
-                        vec![Sound::Eat, Sound::Siren]
-                    )
-                },
-                2 => (
-                    r#""Just a small bite," you muttered, "it won't hurt". Then you ran to the bathroom to puke. The customer got angry waiting and left."#.to_string(),
-                    CustomerKill,
-                    vec![Sound::Eat, Sound::Flush]
-                ),
-                3 => ("Mmm, tasty!".to_string(), NoEffect, vec![Sound::Eat]),
-                _ => panic!()
-            },
-            Gun => match rng.gen_range(0..4) {
-                0 => {
-                    let money = random_money(1000);
-                    (
-                        format!("You accidentally shot yourself in the foot! An ambulance is on the way! Better have the {money} in hand!"),
-                        MoneyLoss(money),
-                        vec![Sound::Gunshot, Sound::LargeHit, Sound::Siren]
-                    )
-                },
-                1 => (
-                    "The bullet flew across the junkyard, ricocheting on walls, chests and the stop sign, finally arriving at the customers head.".to_string(),
-                    CustomerKill,
-                    vec![Sound::Gunshot, Sound::Death]
-                ),
-                2 => {
-                    let money = random_money(250);
-                    (format!("The illegal firearm discharge was reported to the police, the fine is {money}!"), MoneyLoss(money), vec![Sound::Gunshot, Sound::Siren])
-                }
-                3 => {
-                    (
-                        "The bullet flew across the junkyard, ricocheting on walls, chests and the stop sign, finally exiting the building through the window. Let's hope nobody saw that.".to_string(),
-                        NoEffect,
-                        vec![Sound::Gunshot]
-                    )
-                }
-                _ => panic!()
-            },
-            Pill => match rng.gen_range(0..4) {
-                0 => (r#""Mmm, a random pill!", you thought before eating it. Suddenly, your vision became funny."#.to_string(), StatusEffectEnable(Trippy, 2), vec![Sound::Strange]),
-                1 => (
-                    r#"After eating the pill, a sudden burst of energy ran through your body! "Must reorganize everything!" you cried, as you changed the position of all boxes!"#.to_string(),
-                    StatusEffectEnable(Reshuffle, 1),
-                    vec![Sound::Energized]
-                ),
-                2 => ("You know the taste of this pill alright. It is Imodium!".to_string(), CureDiarrhea, vec![Sound::Eat]),
-                3 => {
-                    let money = random_money(1500);
-                    (format!(
-                        "An inspector saw you holding this illegal drug. You paid him {money}. Was it a fine or a bribe? Was he a real inspector? Who knows."),
-                        MoneyLoss(money),
-                        vec![Sound::Siren]
-                    )
-                },
-                _ => panic!()
-            }
-            Screwdriver => match rng.gen_range(0..4) {
-                0 => {
-                    let money = random_money(100);
-                    (format!("You got hurt with this rusty screwdriver and must get a tetanus shot! Have {money} at the ready!"), MoneyLoss(money), vec![Sound::SmallHit])
-                },
-                1 => (
-                    "As this wasn't what you were searching for, you threw it behind you. The scream of the customer confirmed that the hit was fatal.".to_string(),
-                    CustomerKill,
-                    vec![Sound::LargeHit, Sound::Death]
-                ),
-                2 => {
-                    let money = random_money(1000);
-                    (
-                        format!("As this wasn't what you were searching for, you threw it behind you. The scream of the customer confirmed that the hit was not fatal; You got sued for {money} instead."),
-                        MoneyLoss(money),
-                        vec![Sound::SmallHit, Sound::Siren]
-                    )
-                },
-                3 => {
-                    (r#""I have a great idea!" you muttered as you stuck the screwdriver in a power outlet. The electrocution stopped abruptly as the neighborhood transformer exploded. Power will be out for a while, who knows why..."#.to_string(), StatusEffectEnable(LightsOut, 4), vec![Sound::Gunshot])
-                }
-                _ => panic!()
-            }
-        }
+    pub fn gain(&self, rng: &mut impl Rng) -> Money {
+        random_money(self.gain, rng)
     }
 
-    pub fn global_side_effect(&self) -> Option<&'static str> {
-        use Item::*;
-
-        let choices: &[&'static str] = match self {
-            Barrel => &[
-            ],
-            Burger => &[
-                "A food poisoning epidemic ravages restaurant that feeds the homeless, junk food is to blame.",
-                "A person died from a rare strain of streptococcus found in a burger.",
-                "Food inspection closes luxurious restaurant after maggots found in burgers."
-            ],
-            Gun => &[
-                "Ten killed in the largest mass shooting our small town has ever seen.",
-                "Armed burglars take all valuables from jewellery store, see more on page 10.",
-                "Illegal hunting skyrockets as hunters with revoked licenses find new source of illegal guns."
-            ],
-            Pill => &[
-                "Horde of addicts storm police station, fatalities at five and still counting!",
-                "CEO of large company overdosed on unidentified drugs, read page 5 for more!",
-                "Mysterious drug completely cures patient from both cancer and AIDS, scientists baffled, drug is impossible to synthesize in a lab!"
-            ],
-            Screwdriver => &[
-                "Rapist with screwdriver shot and killed before he could commit more atrocities!",
-                "Gun that shoots screwdrivers is the murder weapon, experts say!",
-                "A Rube-Goldberg machine created entirely of screwdrivers falls on the head of the police chief, patrols are doubled!"
-            ],
+    pub fn side_effect(&self, rng: &mut impl Rng) -> (Option<Message>, SideEffect, Vec<String>) {
+        let def = self
+            .side_effects
+            .choose_weighted(rng, |def| def.weight)
+            .unwrap();
+
+        let effect = match def.effect {
+            SideEffect::MoneyLoss(base) => SideEffect::MoneyLoss(random_money(base, rng)),
+            effect => effect,
         };
+        let text = def.text.as_ref().map(|key| match effect {
+            SideEffect::MoneyLoss(money) => Message::new(key).with("money", money),
+            _ => Message::new(key),
+        });
+
+        (text, effect, def.sounds.clone())
+    }
+
+    pub fn global_side_effect(&self, rng: &mut impl Rng) -> Option<Message> {
+        self.news.as_ref().map(|key| Message::pick(key, rng))
+    }
 
-        choices.choose(&mut thread_rng()).copied()
+    pub fn starts_war(&self) -> bool {
+        !self.war_news.is_empty()
     }
 }
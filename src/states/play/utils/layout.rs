@@ -0,0 +1,172 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{
+    asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::HashMap,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    cli_arg,
+    item::{Item, ItemCatalogue},
+};
+
+// An empty board until a run picks one from boards.layouts.ron, or continues a saved one
+#[derive(Clone, Default, Resource, Serialize, Deserialize)]
+pub struct Layout {
+    pub columns: i32,
+    pub rows: i32,
+    pub spacing: f32,
+    // How many chests hold each item, by catalogue id
+    pub stock: Vec<(String, usize)>,
+}
+
+// The boards of boards.layouts.ron by name
+#[derive(Deserialize, TypeUuid)]
+#[uuid = "6d2b8f41-7a3e-4c95-b0d6-1e9f5a7c3b28"]
+pub struct LayoutPresets {
+    pub default: String,
+    pub boards: HashMap<String, Layout>,
+}
+
+impl LayoutPresets {
+    pub fn from_ron(bytes: &[u8]) -> Result<Self, bevy::asset::Error> {
+        let presets: LayoutPresets = ron::de::from_bytes(bytes)?;
+
+        if !presets.boards.contains_key(&presets.default) {
+            return Err(bevy::asset::Error::msg(format!(
+                "default board \"{}\" is not among the boards",
+                presets.default
+            )));
+        }
+
+        for (name, layout) in presets.boards.iter() {
+            if layout.columns < 1 || layout.rows < 1 || layout.spacing <= 0.0 {
+                return Err(bevy::asset::Error::msg(format!(
+                    "board \"{name}\" needs at least one column and row, and some spacing"
+                )));
+            }
+            if layout.stock.iter().all(|(_, count)| *count == 0) {
+                return Err(bevy::asset::Error::msg(format!(
+                    "board \"{name}\" needs at least one item in stock"
+                )));
+            }
+        }
+
+        Ok(presets)
+    }
+
+    // The board named with `--board`, or the default one
+    pub fn chosen(&self) -> Layout {
+        let default = || self.boards[&self.default].clone();
+        let Some(name) = cli_arg::<String>("--board") else { return default() };
+
+        self.boards.get(&name).cloned().unwrap_or_else(|| {
+            let mut names: Vec<&str> = self.boards.keys().map(String::as_str).collect();
+            names.sort();
+            bevy::log::warn!(
+                "Unknown board {name:?}, expected one of {}",
+                names.join(", ")
+            );
+            default()
+        })
+    }
+}
+
+#[derive(Default)]
+pub struct LayoutPresetsLoader;
+
+impl AssetLoader for LayoutPresetsLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let presets = LayoutPresets::from_ron(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(presets));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["layouts.ron"]
+    }
+}
+
+impl Layout {
+    pub fn positions(&self) -> impl Iterator<Item = (i32, i32)> {
+        let rows = self.rows;
+        (0..self.columns).flat_map(move |x| (0..rows).map(move |y| (x, y)))
+    }
+
+    // Items in the order they are handed out to the chests; too short or too long a stock
+    // list is repeated or cut to fit the board
+    pub fn stock(&self, catalogue: &ItemCatalogue) -> Vec<Item> {
+        let mut items: Vec<Item> = self
+            .stock
+            .iter()
+            .filter_map(|(id, count)| {
+                let item = catalogue.item(id);
+                if item.is_none() {
+                    bevy::log::warn!("Layout stocks unknown item \"{id}\"");
+                }
+                item.map(|item| vec![item; *count])
+            })
+            .flatten()
+            .collect();
+
+        // Every chest needs an item, so a stock the catalogue knows nothing of gets one of each
+        if items.is_empty() {
+            bevy::log::error!("Layout stocks no item of the catalogue, stocking one of each");
+            items = catalogue
+                .items
+                .iter()
+                .filter_map(|def| catalogue.item(&def.id))
+                .collect();
+        }
+
+        let chests = (self.columns * self.rows) as usize;
+        if items.len() != chests {
+            bevy::log::warn!("Layout stocks {} items for {} chests", items.len(), chests);
+        }
+
+        items.iter().copied().cycle().take(chests).collect()
+    }
+
+    pub fn chest_translation(&self, (x, y): (i32, i32)) -> Vec3 {
+        Vec3::new(
+            (x as f32 - (self.columns - 1) as f32 / 2.0) * self.spacing,
+            (y as f32 - (self.rows - 1) as f32 / 2.0) * self.spacing,
+            0.0,
+        )
+    }
+
+    pub fn chest_at(&self, point: Vec3) -> Option<(i32, i32)> {
+        let x = (point.x / self.spacing + (self.columns - 1) as f32 / 2.0).round() as i32;
+        let y = (point.y / self.spacing + (self.rows - 1) as f32 / 2.0).round() as i32;
+
+        ((0..self.columns).contains(&x) && (0..self.rows).contains(&y)).then_some((x, y))
+    }
+
+    pub fn first_chest(&self) -> Vec3 {
+        self.chest_translation((0, 0))
+    }
+
+    pub fn camera_rest(&self) -> Transform {
+        // Boards larger than the standard one push the camera back so that they still fit
+        let scale = (self.columns as f32 * self.spacing / 6.0)
+            .max(self.rows as f32 * self.spacing / 4.8)
+            .max(1.0);
+
+        Transform::from_translation(Vec3::new(0.0, -8.0, 17.0) * scale)
+            .looking_at(Vec3::ZERO, Vec3::Y)
+    }
+
+    pub fn camera_first_chest(&self) -> Transform {
+        Transform::from_translation(self.first_chest() + Vec3::new(0.0, -3.2, 1.0))
+            .with_rotation(Quat::from_rotation_x(FRAC_PI_2))
+    }
+}
@@ -1,17 +1,16 @@
 use bevy::prelude::*;
 use lazy_static::lazy_static;
-use std::f32::consts::FRAC_PI_2;
+use serde::Deserialize;
+use std::str::FromStr;
 
-use super::resources::Money;
+use super::{resources::Money, status_effects::StatusEffect};
 
 pub mod item;
+pub mod layout;
+pub mod sound_bank;
 pub mod tween;
 
 lazy_static! {
-    pub static ref CAMERA_REST_POS: Transform =
-        Transform::from_xyz(0.0, -8.0, 17.0).looking_at(Vec3::ZERO, Vec3::Y);
-    pub static ref CAMERA_FIRST_CHEST_POS: Transform =
-        Transform::from_xyz(-2.4, -5.0, 1.0).with_rotation(Quat::from_rotation_x(FRAC_PI_2));
     pub static ref POINT_LIGHT: PointLight = PointLight {
         color: Color::WHITE,
         intensity: 40.0,
@@ -30,20 +29,26 @@ lazy_static! {
     };
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
-pub enum StatusEffect {
-    LightsOut,
-    Trippy,
-    Diarrhea,
-    Cancer,
-    Reshuffle,
-}
-
+#[derive(Clone, Copy, Deserialize)]
 pub enum SideEffect {
     NoEffect,
     MoneyLoss(Money),
     StatusEffectEnable(StatusEffect, i32),
-    CureDiarrhea,
-    ToggleCancer,
+    StatusEffectCure(StatusEffect),
+    // Cures the effect if it is active, or starts it for the given turns otherwise
+    StatusEffectToggle(StatusEffect, i32),
     CustomerKill,
 }
+
+// Reads the value following `flag` on the command line, e.g. `--seed 42`
+pub fn cli_arg<T: FromStr>(flag: &str) -> Option<T> {
+    let arg = std::env::args().skip_while(|arg| arg != flag).nth(1)?;
+
+    match arg.parse() {
+        Ok(value) => Some(value),
+        Err(_) => {
+            bevy::log::warn!("Ignoring invalid value {arg:?} for {flag}");
+            None
+        }
+    }
+}
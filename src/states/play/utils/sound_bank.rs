@@ -0,0 +1,141 @@
+use bevy::{
+    asset::{AssetLoader, AssetPath, BoxedFuture, LoadContext, LoadedAsset},
+    ecs::system::SystemParam,
+    prelude::*,
+    reflect::TypeUuid,
+    utils::HashMap,
+};
+use bevy_kira_audio::prelude::*;
+use rand::prelude::*;
+use serde::Deserialize;
+
+use crate::plugins::audio::Channels;
+
+use super::super::{events::DuckMusic, resources::AssetList};
+
+#[derive(Clone, Copy, Default, Deserialize)]
+pub enum Channel {
+    #[default]
+    Sfx,
+    Music,
+}
+
+fn default_volume() -> f64 {
+    1.0
+}
+
+#[derive(Deserialize)]
+pub struct CueDef {
+    pub clips: Vec<String>,
+    #[serde(skip)]
+    pub handles: Vec<Handle<AudioSource>>,
+    #[serde(default = "default_volume")]
+    pub volume: f64,
+    #[serde(default)]
+    pub pitch_variation: f64,
+    #[serde(default)]
+    pub channel: Channel,
+    // Seconds for which the background music is turned down to make room for this cue
+    #[serde(default)]
+    pub duck: f32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Deserialize)]
+pub enum MusicLayer {
+    Calm,
+    Tense,
+    Muffled,
+}
+
+#[derive(Deserialize)]
+pub struct LayerDef {
+    pub clip: String,
+    #[serde(skip)]
+    pub handle: Handle<AudioSource>,
+    #[serde(default = "default_volume")]
+    pub volume: f64,
+}
+
+#[derive(Deserialize, TypeUuid)]
+#[uuid = "c41e7a0b-93d2-4f6e-8b15-2a7d9e3f0c68"]
+pub struct SoundBank {
+    pub cues: HashMap<String, CueDef>,
+    // Looping background music, all layers play in sync and are crossfaded by volume
+    #[serde(default)]
+    pub music: HashMap<MusicLayer, LayerDef>,
+}
+
+#[derive(SystemParam)]
+pub struct Sounds<'w> {
+    assets: Res<'w, AssetList>,
+    banks: Res<'w, Assets<SoundBank>>,
+    audio: Channels<'w>,
+    ev_duck: EventWriter<'w, DuckMusic>,
+}
+
+impl<'w> Sounds<'w> {
+    // Clip choice and pitch are purely cosmetic, so they stay out of the seeded game randomness
+    pub fn play(&mut self, cue: &str) {
+        let bank = self.banks.get(&self.assets.sounds).unwrap();
+
+        let Some(def) = bank.cues.get(cue) else { bevy::log::warn!("Unknown sound cue \"{cue}\""); return };
+
+        let mut rng = thread_rng();
+        let clip = def.handles.choose(&mut rng).unwrap().clone();
+        let rate = 1.0 + rng.gen_range(-def.pitch_variation..=def.pitch_variation);
+
+        match def.channel {
+            Channel::Sfx => self.audio.sfx.play(clip),
+            Channel::Music => self.audio.music.play(clip),
+        }
+        .with_volume(def.volume)
+        .with_playback_rate(rate);
+
+        if def.duck > 0.0 {
+            self.ev_duck.send(DuckMusic(def.duck));
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct SoundBankLoader;
+
+impl AssetLoader for SoundBankLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let mut bank: SoundBank = ron::de::from_bytes(bytes)?;
+
+            let mut dependencies = Vec::new();
+            for (name, cue) in bank.cues.iter_mut() {
+                if cue.clips.is_empty() {
+                    return Err(bevy::asset::Error::msg(format!(
+                        "sound cue \"{name}\" needs at least one clip"
+                    )));
+                }
+
+                for clip in cue.clips.iter() {
+                    let path: AssetPath<'static> = AssetPath::from(clip.as_str()).to_owned();
+                    cue.handles.push(load_context.get_handle(path.clone()));
+                    dependencies.push(path);
+                }
+            }
+
+            for layer in bank.music.values_mut() {
+                let path: AssetPath<'static> = AssetPath::from(layer.clip.as_str()).to_owned();
+                layer.handle = load_context.get_handle(path.clone());
+                dependencies.push(path);
+            }
+
+            load_context.set_default_asset(LoadedAsset::new(bank).with_dependencies(dependencies));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["bank.ron"]
+    }
+}
@@ -3,7 +3,13 @@ use std::{iter, time::Duration};
 use bevy::prelude::*;
 use bevy_tweening::{lens::*, *};
 
-use super::{CAMERA_FIRST_CHEST_POS, CAMERA_REST_POS};
+// Status effects such as late stage cancer slow down every animation, including those replacing
+// a running one
+pub fn animator(tween: impl Tweenable<Transform> + 'static, speed: f32) -> Animator<Transform> {
+    let mut animator = Animator::new(tween);
+    animator.set_speed(speed);
+    animator
+}
 
 pub fn lift(start: Vec3, height: f32, duration: u64) -> impl Tweenable<Transform> {
     let end = Vec3 { z: height, ..start };
@@ -51,13 +57,13 @@ pub fn move_between(start: Vec3, end: Vec3, max_height: f32) -> impl Tweenable<T
     ))
 }
 
-pub fn camera_to_first_chest() -> impl Tweenable<Transform> {
+pub fn camera_to_first_chest(rest: Transform, first_chest: Transform) -> impl Tweenable<Transform> {
     let trans = Tween::new(
         EaseFunction::QuadraticInOut,
         Duration::from_millis(1000),
         TransformPositionLens {
-            start: CAMERA_REST_POS.translation,
-            end: CAMERA_FIRST_CHEST_POS.translation,
+            start: rest.translation,
+            end: first_chest.translation,
         },
     );
 
@@ -65,21 +71,21 @@ pub fn camera_to_first_chest() -> impl Tweenable<Transform> {
         EaseFunction::QuadraticInOut,
         Duration::from_millis(1000),
         TransformRotationLens {
-            start: CAMERA_REST_POS.rotation,
-            end: CAMERA_FIRST_CHEST_POS.rotation,
+            start: rest.rotation,
+            end: first_chest.rotation,
         },
     );
 
     Tracks::new(iter::once(trans).chain(iter::once(rot)))
 }
 
-pub fn camera_to_rest() -> impl Tweenable<Transform> {
+pub fn camera_to_rest(first_chest: Transform, rest: Transform) -> impl Tweenable<Transform> {
     let trans = Tween::new(
         EaseFunction::QuadraticInOut,
         Duration::from_millis(1000),
         TransformPositionLens {
-            start: CAMERA_FIRST_CHEST_POS.translation,
-            end: CAMERA_REST_POS.translation,
+            start: first_chest.translation,
+            end: rest.translation,
         },
     );
 
@@ -87,8 +93,8 @@ pub fn camera_to_rest() -> impl Tweenable<Transform> {
         EaseFunction::QuadraticInOut,
         Duration::from_millis(1000),
         TransformRotationLens {
-            start: CAMERA_FIRST_CHEST_POS.rotation,
-            end: CAMERA_REST_POS.rotation,
+            start: first_chest.rotation,
+            end: rest.rotation,
         },
     );
 